@@ -322,6 +322,7 @@ fn give_target_auto(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn give_target_on_click(
     mut commands: Commands,
     mut object_query: Query<(Entity, &Transform, &mut Object)>,
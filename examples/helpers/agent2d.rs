@@ -1,16 +1,18 @@
 use bevy::{color::palettes, prelude::*};
-use rand::Rng;
 use vleue_navigator2d::prelude::*;
 
 #[derive(Component)]
 pub struct Navigator {
-    delta: f32,
     speed: f32,
 }
 
 #[derive(Component)]
 pub struct SpecialNavmeshId(pub Entity);
 
+/// Limits the areas a navigator can cross.
+#[derive(Component)]
+pub struct NavigatorFilter(pub AreaFilter);
+
 #[derive(Component)]
 pub struct Path {
     current: Vec2,
@@ -31,23 +33,28 @@ pub fn setup_agent<const SIZE: u32, const SPEED_SIZE: u32, const AGENT_NUM: u32>
             Transform::from_translation(Vec3::new(0.0, 0.0, 1.0))
                 .with_scale(Vec3::splat(SIZE as f32)),
             Navigator {
-                delta: 0.0,
                 speed: SPEED_SIZE as f32 * 5.0,
             },
         ));
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn give_target_to_navigator<const SIZE: u32, const X: u32, const Y: u32>(
     mut commands: Commands,
     navigator: Query<
-        (Entity, &Transform, Option<&SpecialNavmeshId>),
+        (
+            Entity,
+            &Transform,
+            Option<&SpecialNavmeshId>,
+            Option<&NavigatorFilter>,
+        ),
         (With<Navigator>, Without<Path>),
     >,
     navmeshes: Res<Assets<NavMesh>>,
     navmesh: Query<&ManagedNavMesh>,
 ) {
-    for (entity, transform, special_navmesh_id) in &navigator {
+    for (entity, transform, special_navmesh_id, filter) in &navigator {
         let navmesh = match special_navmesh_id {
             Some(navmesh_id) => navmesh.get(navmesh_id.0).expect("navmesh not found"),
             None => return,
@@ -56,20 +63,13 @@ pub fn give_target_to_navigator<const SIZE: u32, const X: u32, const Y: u32>(
         let Some(navmesh) = navmeshes.get(navmesh) else {
             continue;
         };
-        let mut x = 1.0;
-        let mut y = 1.0;
-        for _ in 0..10 {
-            x = rand::rng().random_range(0.0..(X as f32));
-            y = rand::rng().random_range(0.0..(Y as f32));
-
-            if navmesh.is_in_mesh(Vec2::new(x, y)) {
-                break;
-            }
-        }
-        let Some(path) = navmesh.transformed_path(
-            transform.translation.xyz(),
-            navmesh.transform().transform_point(Vec3::new(x, y, 0.0)),
-        ) else {
+        let Some(target) = navmesh.transformed_random_point(&mut rand::rng()) else {
+            continue;
+        };
+        let filter = filter.map(|filter| filter.0).unwrap_or_default();
+        let Some(path) =
+            navmesh.transformed_path_filtered(transform.translation.xyz(), target, &filter)
+        else {
             break;
         };
         if let Some((first, remaining)) = path.path.split_first() {
@@ -97,6 +97,7 @@ pub fn give_target_to_navigator<const SIZE: u32, const X: u32, const Y: u32>(
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn refresh_path<const SIZE: u32, const X: u32, const Y: u32>(
     mut commands: Commands,
     mut navigators: Query<(
@@ -104,38 +105,37 @@ pub fn refresh_path<const SIZE: u32, const X: u32, const Y: u32>(
         &Transform,
         &mut Path,
         Option<&SpecialNavmeshId>,
-        &mut Navigator,
+        Option<&NavigatorFilter>,
     )>,
-    mut navmeshes: ResMut<Assets<NavMesh>>,
-    navmesh: Query<(&ManagedNavMesh, Ref<NavMeshStatus>)>,
+    navmeshes: Res<Assets<NavMesh>>,
+    navmesh: Query<&ManagedNavMesh>,
     transforms: Query<&Transform>,
 ) {
-    for (entity, transform, mut path, special_navmesh_id, mut navigator) in &mut navigators {
-        let (navmesh_handle, status) = match special_navmesh_id {
+    for (entity, transform, mut path, special_navmesh_id, filter) in &mut navigators {
+        let navmesh_handle = match special_navmesh_id {
             Some(navmesh_id) => navmesh.get(navmesh_id.0).expect("navmesh not found"),
             None => navmesh.iter().next().expect("no navmesh found"),
         };
-
-        if (!status.is_changed() || *status != NavMeshStatus::Built) && navigator.delta != 0.0 {
-            return;
-        }
-        let Some(navmesh) = navmeshes.get_mut(navmesh_handle) else {
+        let Some(navmesh) = navmeshes.get(navmesh_handle) else {
             return;
         };
 
         let target = transforms.get(path.target).unwrap().translation.xy();
-        if !navmesh.transformed_is_in_mesh(transform.translation) {
-            navigator.delta += 0.1;
-            navmesh.set_search_delta(navigator.delta);
+        // An obstacle may have moved over the agent, start from the closest point still in the mesh
+        let Some(start) =
+            navmesh.transformed_closest_point(transform.translation, SIZE as f32 * 2.0)
+        else {
             continue;
-        }
+        };
         if !navmesh.transformed_is_in_mesh(target.extend(0.0)) {
             commands.entity(path.target).despawn();
             commands.entity(entity).remove::<Path>();
             continue;
         }
 
-        let Some(new_path) = navmesh.transformed_path(transform.translation, target.extend(0.0))
+        let filter = filter.map(|filter| filter.0).unwrap_or_default();
+        let Some(new_path) =
+            navmesh.transformed_path_filtered(start.point, target.extend(0.0), &filter)
         else {
             commands.entity(path.target).despawn();
             commands.entity(entity).remove::<Path>();
@@ -146,7 +146,6 @@ pub fn refresh_path<const SIZE: u32, const X: u32, const Y: u32>(
             remaining.reverse();
             path.current = first.xy();
             path.next = remaining;
-            navigator.delta = 0.0;
         }
     }
 }
@@ -300,6 +300,7 @@ pub fn display_settings(
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn update_settings<const STEP: u32>(
     mut interaction_query: Query<
         (
@@ -70,6 +70,7 @@ fn main() {
 enum TaskMode {
     Async,
     Blocking,
+    Batch,
 }
 
 #[derive(Resource)]
@@ -123,6 +124,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         });
 }
 
+#[allow(clippy::too_many_arguments)]
 fn on_mesh_change(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -248,16 +250,48 @@ fn compute_paths(
     primary_window: Single<&Window, With<PrimaryWindow>>,
     task_mode: Res<TaskMode>,
     mesh: Res<Meshes>,
+    mut stats: ResMut<Stats>,
 ) {
     let mesh = if let Some(mesh) = meshes.get(&mesh.aurora) {
         mesh
     } else {
         return;
     };
-    for (entity, target, transform) in &with_target {
-        let window = *primary_window;
-        let factor = (window.width() / MESH_SIZE.x).min(window.height() / MESH_SIZE.y);
+    let window = *primary_window;
+    let factor = (window.width() / MESH_SIZE.x).min(window.height() / MESH_SIZE.y);
+
+    if *task_mode == TaskMode::Batch {
+        // All the paths requested this frame are computed together in parallel
+        let (entities, queries): (Vec<_>, Vec<_>) = with_target
+            .iter()
+            .map(|(entity, target, transform)| {
+                let in_mesh = transform.translation.truncate() / factor + MESH_SIZE / 2.0;
+                (entity, (in_mesh, target.target))
+            })
+            .unzip();
+        if queries.is_empty() {
+            return;
+        }
+        let start = Instant::now();
+        let paths = mesh.paths_batch(&queries);
+        let duration = (Instant::now() - start).as_secs_f32() / queries.len() as f32;
+        for ((entity, path), (from, _)) in entities.into_iter().zip(paths).zip(queries) {
+            stats.pathfinding_duration.push_front(duration);
+            stats.task_delay.push_front(0.0);
+            if let Some(path) = path {
+                commands.entity(entity).insert(Path { path: path.path });
+            } else if !mesh.is_in_mesh(from) {
+                commands.entity(entity).despawn();
+            } else {
+                commands.entity(entity).remove::<Target>();
+            }
+        }
+        stats.pathfinding_duration.truncate(100);
+        stats.task_delay.truncate(100);
+        return;
+    }
 
+    for (entity, target, transform) in &with_target {
         let in_mesh = transform.translation.truncate() / factor + MESH_SIZE / 2.0;
 
         let to = target.target;
@@ -361,6 +395,7 @@ fn move_navigator(
         });
 }
 
+#[allow(clippy::type_complexity)]
 fn go_somewhere(
     query: Query<
         Entity,
@@ -424,7 +459,8 @@ fn mode_change(keyboard_input: Res<ButtonInput<KeyCode>>, mut task_mode: ResMut<
     if keyboard_input.just_pressed(KeyCode::Space) {
         match *task_mode {
             TaskMode::Async => *task_mode = TaskMode::Blocking,
-            TaskMode::Blocking => *task_mode = TaskMode::Async,
+            TaskMode::Blocking => *task_mode = TaskMode::Batch,
+            TaskMode::Batch => *task_mode = TaskMode::Async,
         }
     }
 }
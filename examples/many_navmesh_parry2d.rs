@@ -11,7 +11,7 @@ use std::{f32::consts::PI, ops::Deref};
 use vleue_navigator2d::prelude::*;
 
 use crate::{
-    agent::{Navigator, SpecialNavmeshId},
+    agent::{Navigator, NavigatorFilter, SpecialNavmeshId},
     ui::ShowingNavMesh,
 };
 #[path = "helpers/agent2d.rs"]
@@ -22,11 +22,8 @@ mod ui;
 const MESH_WIDTH: u32 = 150;
 const MESH_HEIGHT: u32 = 100;
 
-#[derive(Debug, Component)]
-pub struct LandNavMesh;
-
-#[derive(Debug, Component)]
-pub struct AirNavMesh;
+/// Flag of the areas that can only be crossed by air units.
+const WALL: u32 = 1 << 1;
 
 fn main() {
     App::new()
@@ -84,10 +81,10 @@ fn setup(
 ) {
     commands.spawn(Camera2d);
     // Spawn a new navmesh that will be automatically updated.
-    let land_navmesh = commands
+    // It's shared by land and air units, which use a different filter on its areas.
+    let navmesh = commands
         .spawn((
-            LandNavMesh,
-            ManagedNavMesh::from_id(0),
+            ManagedNavMesh::single(),
             NavMeshSettings {
                 // Define the outer borders of the navmesh.
                 // This will be in navmesh coordinates
@@ -116,44 +113,26 @@ fn setup(
         ))
         .id();
 
-    // Set the currently displayed navmesh to the land navmesh entity.
-    showing_navmesh.0 = Some(land_navmesh);
+    // Set the currently displayed navmesh to the navmesh entity.
+    showing_navmesh.0 = Some(navmesh);
 
-    let air_navmesh = commands
-        .spawn((
-            AirNavMesh,
-            ManagedNavMesh::from_id(1),
-            NavMeshSettings {
-                fixed: Triangulation::from_outer_edges(&[
-                    vec2(0.0, 0.0),
-                    vec2(MESH_WIDTH as f32, 0.0),
-                    vec2(MESH_WIDTH as f32, MESH_HEIGHT as f32),
-                    vec2(0.0, MESH_HEIGHT as f32),
-                ]),
-                simplify: 0.05,
-                filter_obstacles_mode: FilterObstaclesMode::Ignore,
-                ..default()
-            },
-            NavMeshUpdateMode::Direct,
-            Transform::from_translation(Vec3::new(
-                -(MESH_WIDTH as f32) / 2.0 * FACTOR,
-                -(MESH_HEIGHT as f32) / 2.0 * FACTOR,
-                0.0,
-            ))
-            .with_scale(Vec3::splat(FACTOR)),
-        ))
-        .id();
-
-    let navmeshs = [land_navmesh, air_navmesh];
+    // Land units can't cross walls, air units can go anywhere in the navmesh.
+    let filters = [
+        AreaFilter {
+            exclude: WALL,
+            ..default()
+        },
+        AreaFilter::default(),
+    ];
     let colors = [palettes::css::RED, palettes::css::FUCHSIA];
     for (index, (entity, mut sprite)) in navigators.iter_mut().enumerate() {
         sprite.color = colors[index].into();
         commands
             .entity(entity)
-            .insert(SpecialNavmeshId(navmeshs[index]));
+            .insert((SpecialNavmeshId(navmesh), NavigatorFilter(filters[index])));
         info!(
-            "Navigator entity: {:?} use the navmesh id is {:?}",
-            entity, navmeshs[index]
+            "Navigator entity: {:?} use the filter {:?}",
+            entity, filters[index]
         );
     }
 }
@@ -225,6 +204,11 @@ fn new_obstacle(commands: &mut Commands, rng: &mut ThreadRng, transform: Transfo
                 _ => unreachable!(),
             },
             transform,
+            // Obstacles are walls that air units can fly over.
+            NavMeshArea {
+                cost: 1.0,
+                flags: WALL,
+            },
         ))
         .id()
 }
@@ -236,7 +220,7 @@ fn display_mesh(
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut current_mesh_entity: Local<Option<Entity>>,
     window_resized: EventReader<WindowResized>,
-    navmesh: Single<(&ManagedNavMesh, Ref<NavMeshStatus>), With<LandNavMesh>>,
+    navmesh: Single<(&ManagedNavMesh, Ref<NavMeshStatus>)>,
 ) {
     let (navmesh_handle, status) = navmesh.deref();
     if (!status.is_changed() || **status != NavMeshStatus::Built) && window_resized.is_empty() {
@@ -276,7 +260,6 @@ fn spawn_obstacle_on_click(
     primary_window: Single<&Window, With<PrimaryWindow>>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     mut commands: Commands,
-    mut settings: Single<&mut NavMeshSettings, With<AirNavMesh>>,
 ) -> Result {
     if mouse_button_input.just_pressed(MouseButton::Right) {
         let Ok((camera, camera_transform)) = camera_q.single() else {
@@ -291,9 +274,7 @@ fn spawn_obstacle_on_click(
             let mut rng = rand::rng();
             let transform = Transform::from_translation(position.extend(0.0))
                 .with_rotation(Quat::from_rotation_z(rng.random_range(0.0..(2.0 * PI))));
-            settings
-                .filter_obstacles
-                .insert(new_obstacle(&mut commands, &mut rng, transform));
+            new_obstacle(&mut commands, &mut rng, transform);
         }
     }
     Ok(())
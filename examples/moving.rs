@@ -129,6 +129,7 @@ fn setup(
     commands.insert_resource(AURORA);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn on_mesh_change(
     mesh: Res<MeshDetails>,
     mut commands: Commands,
@@ -242,11 +243,11 @@ fn mesh_change(
     if mouse_input.just_released(MouseButton::Left) {
         *pressed_since = None;
     }
-    if let Some(started) = *pressed_since {
-        if (time.elapsed() - started).as_secs() > 1 {
-            touch_triggered = true;
-            *pressed_since = None;
-        }
+    if let Some(started) = *pressed_since
+        && (time.elapsed() - started).as_secs() > 1
+    {
+        touch_triggered = true;
+        *pressed_since = None;
     }
     if keyboard_input.just_pressed(KeyCode::Space) || touch_triggered {
         match mesh.mesh {
@@ -273,6 +274,7 @@ struct Path {
     path: Vec<Vec2>,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn on_click(
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     primary_window: Single<&Window, With<PrimaryWindow>>,
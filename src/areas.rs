@@ -0,0 +1,773 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::{Component, TransformPoint},
+};
+use polyanya::{Layer, Path, Polygon, Triangulation, Vertex};
+
+use crate::{
+    NavMesh, TransformedPath,
+    closest_point::ClosestPoint,
+    raycast::walk,
+    topology::{self, EPSILON, PolygonRef},
+};
+
+/// Number of segments each edge is split into when looking for where to cross it, when searching
+/// a path that can't be found by Polyanya.
+const EDGE_SAMPLES: usize = 4;
+
+/// Marks an obstacle as a walkable area with a traversal cost and flags instead.
+///
+/// The polygons given by the obstacle source on the same entity are cut into the [`NavMesh`] by the
+/// [`NavmeshUpdaterPlugin`](crate::prelude::NavmeshUpdaterPlugin), and the polygons inside them get the
+/// `cost` and `flags` of the area. Path queries can be limited to some flags with an [`AreaFilter`], and
+/// prefer the cheapest route over the shortest one once enabled with [`NavMesh::set_weighted_paths`].
+///
+/// Where areas overlap, the one with the lowest cost is used. Polygons outside of any area have the
+/// default values.
+///
+/// Obstacles don't have flags as they are removed from the [`NavMesh`]. An area with a flag that some
+/// [`AreaFilter`]s exclude acts as an obstacle for them only.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct NavMeshArea {
+    /// Multiplier applied to the distance traveled in this area. The default value is `1.0`.
+    ///
+    /// It should be greater than `0.0`. It's only used by path queries when [`NavMesh::set_weighted_paths`]
+    /// is enabled.
+    pub cost: f32,
+    /// Bitmask of the kinds of terrain in this area, for example land, shallow water or deep water.
+    ///
+    /// The same bits are used by [`NavMesh::close_gates`]: closing a flag closes every area with it.
+    ///
+    /// The default value is [`NavMeshArea::DEFAULT_FLAGS`].
+    pub flags: u32,
+}
+
+impl NavMeshArea {
+    /// Flags of the polygons outside of any area.
+    pub const DEFAULT_FLAGS: u32 = 1;
+}
+
+impl Default for NavMeshArea {
+    fn default() -> Self {
+        Self {
+            cost: 1.0,
+            flags: Self::DEFAULT_FLAGS,
+        }
+    }
+}
+
+/// Limits the polygons that can be crossed by a path, using their [`NavMeshArea::flags`].
+///
+/// A polygon can be crossed if it has at least one of the `include` flags, and none of the `exclude` flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AreaFilter {
+    /// Flags allowed. The default value allows all flags.
+    pub include: u32,
+    /// Flags forbidden. The default value doesn't forbid any flag.
+    pub exclude: u32,
+}
+
+impl Default for AreaFilter {
+    fn default() -> Self {
+        Self {
+            include: u32::MAX,
+            exclude: 0,
+        }
+    }
+}
+
+impl AreaFilter {
+    /// Checks if a polygon with these flags can be crossed.
+    pub fn allows(&self, flags: u32) -> bool {
+        flags & self.include != 0 && flags & self.exclude == 0
+    }
+}
+
+/// Cuts the outlines of `areas` into the triangles of `layer`, then merges polygons with the same cost.
+///
+/// Returns the new layer and the area of each of its polygons.
+pub(crate) fn cut_areas(
+    mut layer: Layer,
+    areas: &[(Vec<Vec2>, NavMeshArea)],
+    merge_steps: usize,
+) -> (Layer, Vec<NavMeshArea>) {
+    // Outlines are added as clockwise polygons: their edges cut the triangulation, but no point is
+    // ever inside them so they don't add to the walkable surface
+    for (outline, _) in areas {
+        let first = layer.vertices.len() as u32;
+        layer.vertices.extend(
+            outline
+                .iter()
+                .map(|coords| Vertex::new(*coords, vec![u32::MAX])),
+        );
+        let mut vertices = (first..first + outline.len() as u32).collect::<Vec<_>>();
+        if topology::area(outline) > 0.0 {
+            vertices.reverse();
+        }
+        layer.polygons.push(Polygon::new(vertices, false));
+    }
+    layer.bake_polygon_finder();
+    let cut = Triangulation::from_mesh_layer(layer).as_layer();
+
+    let polygon_areas = cut
+        .polygons
+        .iter()
+        .map(|polygon| {
+            let coords = polygon
+                .vertices
+                .iter()
+                .map(|v| cut.vertices[*v as usize].coords)
+                .collect::<Vec<_>>();
+            let center = topology::centroid(&coords);
+            areas
+                .iter()
+                .filter(|(outline, _)| topology::inside(outline, center))
+                .map(|(_, area)| *area)
+                .min_by(|a, b| a.cost.total_cmp(&b.cost))
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    // Merge each group of polygons with the same area on its own, so that they are not merged
+    // across the outlines of the areas
+    let mut groups: Vec<(NavMeshArea, Vec<usize>)> = vec![];
+    for (index, area) in polygon_areas.iter().enumerate() {
+        match groups.iter_mut().find(|(group, _)| group == area) {
+            Some((_, members)) => members.push(index),
+            None => groups.push((*area, vec![index])),
+        }
+    }
+    let mut merged = Layer::default();
+    merged.vertices = cut.vertices.clone();
+    let mut merged_areas = vec![];
+    for (area, members) in groups {
+        let mut in_group = vec![u32::MAX; cut.polygons.len()];
+        for (new, old) in members.iter().enumerate() {
+            in_group[*old] = new as u32;
+        }
+        let mut group = Layer::default();
+        group.vertices = cut
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let polygons = vertex
+                    .polygons
+                    .iter()
+                    .map(|p| in_group.get(*p as usize).copied().unwrap_or(u32::MAX))
+                    .collect();
+                Vertex::new(vertex.coords, polygons)
+            })
+            .collect();
+        group.polygons = members
+            .iter()
+            .map(|index| cut.polygons[*index].clone())
+            .collect();
+        for _ in 0..merge_steps {
+            group.merge_polygons();
+        }
+        merged_areas.extend(group.polygons.iter().map(|_| area));
+        merged.polygons.extend(group.polygons);
+    }
+    link_vertices(&mut merged);
+    (merged, merged_areas)
+}
+
+/// Sets the polygons around each vertex of a layer, in counter clockwise order, with `u32::MAX`
+/// where there is no polygon between two of them.
+fn link_vertices(layer: &mut Layer) {
+    let mut around = vec![vec![]; layer.vertices.len()];
+    for (index, polygon) in layer.polygons.iter().enumerate() {
+        let count = polygon.vertices.len();
+        for (position, vertex) in polygon.vertices.iter().enumerate() {
+            around[*vertex as usize].push((
+                index as u32,
+                polygon.vertices[(position + 1) % count],
+                polygon.vertices[(position + count - 1) % count],
+            ));
+        }
+    }
+    for (vertex, mut polygons) in around.into_iter().enumerate() {
+        let coords = layer.vertices[vertex].coords;
+        let angle = |other: u32| {
+            let direction = layer.vertices[other as usize].coords - coords;
+            direction.y.atan2(direction.x)
+        };
+        // Each polygon goes counter clockwise from its next vertex to its previous one
+        polygons.sort_by(|(_, a, _), (_, b, _)| angle(*a).total_cmp(&angle(*b)));
+        let mut linked = vec![];
+        for (i, (polygon, _, previous)) in polygons.iter().enumerate() {
+            linked.push(*polygon);
+            let (_, following, _) = polygons[(i + 1) % polygons.len()];
+            if following != *previous {
+                linked.push(u32::MAX);
+            }
+        }
+        if linked.is_empty() {
+            linked.push(u32::MAX);
+        }
+        layer.vertices[vertex] = Vertex::new(coords, linked);
+    }
+}
+
+/// Points of a route, with the polygon crossed to reach them and the cost so far.
+type Route = Vec<(Vec2, PolygonRef, f32)>;
+
+struct Step {
+    estimate: f32,
+    node: usize,
+}
+
+impl PartialEq for Step {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Step {}
+
+impl PartialOrd for Step {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Step {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed to make the `BinaryHeap` a min-heap
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl NavMesh {
+    /// Area of a polygon, the default [`NavMeshArea`] for polygons outside of any area.
+    pub fn polygon_area(&self, polygon: PolygonRef) -> NavMeshArea {
+        self.areas
+            .get(polygon.layer as usize)
+            .and_then(|layer| layer.get(polygon.polygon as usize))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Traversal cost multiplier of a polygon, `1.0` for polygons outside of any [`NavMeshArea`].
+    pub fn polygon_cost(&self, polygon: PolygonRef) -> f32 {
+        self.polygon_area(polygon).cost
+    }
+
+    /// Flags of a polygon, [`NavMeshArea::DEFAULT_FLAGS`] for polygons outside of any [`NavMeshArea`].
+    pub fn polygon_flags(&self, polygon: PolygonRef) -> u32 {
+        self.polygon_area(polygon).flags
+    }
+
+    /// Sets the area of each polygon of a layer, by polygon index.
+    ///
+    /// Polygons without an area have the default [`NavMeshArea`]. This clears the path cache.
+    pub fn set_layer_areas(&mut self, layer: u8, areas: Vec<NavMeshArea>) {
+        if self.areas.len() <= layer as usize {
+            self.areas.resize(layer as usize + 1, vec![]);
+        }
+        self.areas[layer as usize] = areas;
+        self.update_cheapest_cost();
+        self.link_graphs = Default::default();
+        self.clear_path_cache();
+    }
+
+    /// Sets the area of each polygon of each layer, and keeps the cheapest cost up to date.
+    pub(crate) fn set_areas(&mut self, areas: Vec<Vec<NavMeshArea>>) {
+        self.areas = areas;
+        self.update_cheapest_cost();
+        self.link_graphs = Default::default();
+    }
+
+    /// Makes path queries prefer cheaper paths, using the [`NavMeshArea::cost`] of the polygons crossed. This is
+    /// disabled by default, and clears the path cache.
+    ///
+    /// When disabled, traversal costs are ignored and paths are the shortest ones, found by Polyanya. When enabled,
+    /// paths on a mesh with traversal costs are searched by crossing each edge at a few points along it, then
+    /// shortened where a straight line is not more expensive. This is slower, and only an approximation: the path
+    /// found can be slightly more expensive than the cheapest one.
+    pub fn set_weighted_paths(&mut self, weighted: bool) {
+        self.weighted_paths = weighted;
+        self.update_cheapest_cost();
+        self.link_graphs = Default::default();
+        self.clear_path_cache();
+    }
+
+    /// Checks if path queries prefer cheaper paths. See [`NavMesh::set_weighted_paths`].
+    pub fn weighted_paths(&self) -> bool {
+        self.weighted_paths
+    }
+
+    fn update_cheapest_cost(&mut self) {
+        if !self.weighted_paths {
+            self.cheapest_cost = 1.0;
+            return;
+        }
+        self.cheapest_cost = topology::polygons(&self.mesh)
+            .map(|polygon| self.polygon_cost(polygon))
+            .fold(f32::INFINITY, f32::min)
+            .max(EPSILON);
+        if !self.cheapest_cost.is_finite() {
+            self.cheapest_cost = 1.0;
+        }
+    }
+
+    /// Checks if weighted paths are enabled, and some polygons have a traversal cost other than `1.0`.
+    pub(crate) fn has_costs(&self) -> bool {
+        self.weighted_paths && self.areas.iter().flatten().any(|area| area.cost != 1.0)
+    }
+
+    /// Cost of a polygon, infinite if it can't be crossed with this filter.
+    ///
+    /// This is `1.0` for all other polygons when weighted paths are disabled.
+    pub(crate) fn filtered_cost(&self, polygon: PolygonRef, filter: &AreaFilter) -> f32 {
+        let area = self.polygon_area(polygon);
+        if !filter.allows(area.flags) {
+            f32::INFINITY
+        } else if self.weighted_paths {
+            area.cost
+        } else {
+            1.0
+        }
+    }
+
+    /// Finds a path between two points, only crossing polygons allowed by `filter`.
+    ///
+    /// The shortest path is kept if it only crosses allowed polygons. Otherwise, a path around the polygons not
+    /// allowed is searched like for [weighted paths](NavMesh::set_weighted_paths), and can be slightly longer than
+    /// the shortest one. Like [`NavMesh::path`], this prefers cheaper paths if weighted paths are enabled. Paths
+    /// found with a filter other than the default one are not cached.
+    pub fn path_filtered(&self, from: Vec2, to: Vec2, filter: &AreaFilter) -> Option<Path> {
+        if *filter == AreaFilter::default() {
+            return self.path(from, to);
+        }
+        self.uncached_path(from, to, filter)
+    }
+
+    /// Finds a path between two points with the cheapest method for this mesh, without going through
+    /// the path cache. Closed gates are added to the flags excluded by `filter`.
+    pub(crate) fn uncached_path(&self, from: Vec2, to: Vec2, filter: &AreaFilter) -> Option<Path> {
+        let filter = AreaFilter {
+            exclude: filter.exclude | self.closed_gates,
+            ..*filter
+        };
+        if !self.links.is_empty() {
+            self.path_with_links(from, to, &filter)
+        } else if !self.may_be_connected(from, to) {
+            None
+        } else if self.has_costs() {
+            self.weighted_path(from, to, &filter).map(|(path, _)| path)
+        } else if filter != AreaFilter::default() {
+            // The shortest path is kept if it doesn't cross a polygon excluded by the filter
+            let path = self.mesh.path(from, to)?;
+            let allowed = self.corridor(from, &path).is_some_and(|corridor| {
+                corridor
+                    .polygons
+                    .iter()
+                    .all(|polygon| filter.allows(self.polygon_flags(*polygon)))
+            });
+            if allowed {
+                Some(path)
+            } else {
+                self.weighted_path(from, to, &filter).map(|(path, _)| path)
+            }
+        } else {
+            self.mesh.path(from, to)
+        }
+    }
+
+    /// Finds a path between two points, only crossing polygons allowed by `filter`.
+    ///
+    /// Inputs and results are transformed using the [`NavMesh::transform`].
+    pub fn transformed_path_filtered(
+        &self,
+        from: Vec3,
+        to: Vec3,
+        filter: &AreaFilter,
+    ) -> Option<TransformedPath> {
+        let inner_from = self.world_to_mesh().transform_point(from).xy();
+        let inner_to = self.world_to_mesh().transform_point(to).xy();
+        self.path_filtered(inner_from, inner_to, filter)
+            .map(|path| self.transform_path(path))
+    }
+
+    /// Finds a cheap path between two points and its cost, using the cost of each polygon if weighted
+    /// paths are enabled and skipping the ones not allowed by `filter`.
+    ///
+    /// Edges are crossed at a few points along them, then the path is shortened where a straight
+    /// line is not more expensive. This is an approximation: the path found can be slightly more
+    /// expensive than the cheapest one, as crossing an edge between those points is never considered.
+    pub(crate) fn weighted_path(
+        &self,
+        from: Vec2,
+        to: Vec2,
+        filter: &AreaFilter,
+    ) -> Option<(Path, f32)> {
+        // Points slightly out of the mesh are moved inside, like in `NavMesh::path`
+        let ClosestPoint {
+            point: from,
+            polygon: start,
+            ..
+        } = self.closest_point(from, self.snap_distance())?;
+        let ClosestPoint {
+            point: to,
+            polygon: goal,
+            ..
+        } = self.closest_point(to, self.snap_distance())?;
+        let (_, route) = self.cheapest_route((start, from), &[(goal, to)], filter)?;
+
+        let mut path = Path {
+            length: 0.0,
+            path: vec![],
+            #[cfg(feature = "detailed-layers")]
+            path_with_layers: vec![],
+        };
+        let mut total = 0.0;
+        let mut index = 0;
+        while index < route.len() - 1 {
+            let (point, _, cost) = route[index];
+            let leaving = route[index + 1].1;
+            let (furthest, step_cost) = (index + 2..route.len())
+                .rev()
+                .find_map(|next| {
+                    self.segment_cost(leaving, point, route[next].0, filter)
+                        .filter(|straight| *straight <= route[next].2 - cost + EPSILON)
+                        .map(|straight| (next, straight))
+                })
+                .unwrap_or((index + 1, route[index + 1].2 - cost));
+            total += step_cost;
+            let (next_point, crossed, _) = route[furthest];
+            path.length += point.distance(next_point);
+            path.path.push(next_point);
+            #[cfg(feature = "detailed-layers")]
+            path.path_with_layers.push((next_point, crossed.layer));
+            #[cfg(not(feature = "detailed-layers"))]
+            let _ = crossed;
+            index = furthest;
+        }
+        Some((path, total))
+    }
+
+    /// Searches the cheapest route from a point to the closest of several goals, each given with the
+    /// polygon containing it, crossing edges at a few points along them.
+    ///
+    /// Returns the index of the goal reached, and the points of the route with the polygon crossed to
+    /// reach them and the cost so far.
+    pub(crate) fn cheapest_route(
+        &self,
+        (start, from): (PolygonRef, Vec2),
+        goals: &[(PolygonRef, Vec2)],
+        filter: &AreaFilter,
+    ) -> Option<(usize, Route)> {
+        // Keeps the estimate of the remaining cost below the actual cost
+        let cheapest = self.cheapest_cost;
+        let estimate = |point: Vec2| {
+            goals
+                .iter()
+                .map(|(_, goal)| point.distance(*goal))
+                .fold(f32::INFINITY, f32::min)
+                * cheapest
+        };
+
+        // A node is a point on the border of a polygon, from where that polygon is crossed
+        let mut nodes = vec![(start, from)];
+        let mut best = vec![0.0];
+        let mut came_from = vec![usize::MAX];
+        let mut known = HashMap::from([((start, from.to_array().map(f32::to_bits)), 0)]);
+        let mut to_visit = BinaryHeap::from([Step {
+            estimate: estimate(from),
+            node: 0,
+        }]);
+        let mut visited = vec![false];
+
+        let (reached, goal_node) = loop {
+            let Step { node, .. } = to_visit.pop()?;
+            if visited[node] {
+                continue;
+            }
+            visited[node] = true;
+            let (polygon, point) = nodes[node];
+            if let Some(reached) = goals.iter().position(|goal| *goal == (polygon, point)) {
+                break (reached, node);
+            }
+            let cost = self.filtered_cost(polygon, filter);
+            if !cost.is_finite() {
+                continue;
+            }
+
+            let coords = topology::polygon_coords(&self.mesh, polygon);
+            let mut targets = goals
+                .iter()
+                .filter(|(goal, _)| *goal == polygon)
+                .copied()
+                .collect::<Vec<_>>();
+            for (edge, (a, b)) in coords.iter().zip(coords.iter().cycle().skip(1)).enumerate() {
+                let Some(next) = topology::neighbour(&self.mesh, polygon, edge) else {
+                    continue;
+                };
+                targets.extend(
+                    (0..=EDGE_SAMPLES).map(|i| (next, a.lerp(*b, i as f32 / EDGE_SAMPLES as f32))),
+                );
+            }
+
+            for (next, target) in targets {
+                let next_cost = best[node] + point.distance(target) * cost;
+                let id = *known
+                    .entry((next, target.to_array().map(f32::to_bits)))
+                    .or_insert_with(|| {
+                        nodes.push((next, target));
+                        best.push(f32::INFINITY);
+                        came_from.push(usize::MAX);
+                        visited.push(false);
+                        nodes.len() - 1
+                    });
+                if next_cost < best[id] {
+                    best[id] = next_cost;
+                    came_from[id] = node;
+                    to_visit.push(Step {
+                        estimate: next_cost + estimate(target),
+                        node: id,
+                    });
+                }
+            }
+        };
+
+        let mut route = vec![];
+        let mut current = goal_node;
+        while came_from[current] != usize::MAX {
+            let previous = came_from[current];
+            route.push((nodes[current].1, nodes[previous].0, best[current]));
+            current = previous;
+        }
+        route.push((from, start, 0.0));
+        route.reverse();
+        route.dedup_by(|(a, ..), (b, ..)| a == b);
+        Some((reached, route))
+    }
+
+    /// Cost of the straight segment from `from`, in `start`, to `to`, or `None` if it leaves the mesh.
+    fn segment_cost(
+        &self,
+        start: PolygonRef,
+        from: Vec2,
+        to: Vec2,
+        filter: &AreaFilter,
+    ) -> Option<f32> {
+        let walk = walk(&self.mesh, start, from, to);
+        if walk.hit.is_some() {
+            return None;
+        }
+        let direction = to - from;
+        let length = direction.length();
+        if length < EPSILON {
+            return Some(0.0);
+        }
+        let crossings = walk.portals.iter().map(|[a, b]| {
+            let edge = *b - *a;
+            let denominator = direction.perp_dot(edge);
+            let t = if denominator.abs() > f32::EPSILON {
+                (*a - from).perp_dot(edge) / denominator
+            } else {
+                (*a - from).dot(direction) / (length * length)
+            };
+            t.clamp(0.0, 1.0)
+        });
+        let bounds = std::iter::once(0.0)
+            .chain(crossings)
+            .chain(std::iter::once(1.0))
+            .collect::<Vec<_>>();
+        Some(
+            walk.polygons
+                .iter()
+                .zip(bounds.windows(2))
+                .map(|(polygon, t)| {
+                    (t[1] - t[0]).max(0.0) * length * self.filtered_cost(*polygon, filter)
+                })
+                .sum(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use super::*;
+
+    #[test]
+    fn linked_like_triangulation() {
+        let mut triangulation = Triangulation::from_outer_edges(&[
+            vec2(0.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(0.0, 10.0),
+        ]);
+        triangulation.add_obstacles(vec![vec![
+            vec2(4.0, 4.0),
+            vec2(6.0, 4.0),
+            vec2(6.0, 6.0),
+            vec2(4.0, 6.0),
+        ]]);
+        let layer = triangulation.as_layer();
+        let mut linked = layer.clone();
+        link_vertices(&mut linked);
+        for (vertex, expected) in linked.vertices.iter().zip(&layer.vertices) {
+            // Lists can start from a different polygon
+            let rotation = (0..vertex.polygons.len()).find(|r| {
+                let mut rotated = vertex.polygons.clone();
+                rotated.rotate_left(*r);
+                rotated == expected.polygons
+            });
+            assert!(
+                rotation.is_some(),
+                "{:?} != {:?}",
+                vertex.polygons,
+                expected.polygons
+            );
+        }
+    }
+
+    #[test]
+    fn prefer_cheap_areas() {
+        let square = vec![
+            vec2(0.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(0.0, 10.0),
+        ];
+        let layer = Triangulation::from_outer_edges(&square).as_layer();
+        // Mud in the middle of the square, with a road going around it at the top
+        let mud = vec![
+            vec2(2.0, 0.0),
+            vec2(8.0, 0.0),
+            vec2(8.0, 7.0),
+            vec2(2.0, 7.0),
+        ];
+        let road = vec![
+            vec2(0.0, 8.0),
+            vec2(10.0, 8.0),
+            vec2(10.0, 9.0),
+            vec2(0.0, 9.0),
+        ];
+        let (mut layer, areas) = cut_areas(
+            layer,
+            &[
+                (
+                    mud,
+                    NavMeshArea {
+                        cost: 10.0,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    road,
+                    NavMeshArea {
+                        cost: 0.5,
+                        ..Default::default()
+                    },
+                ),
+            ],
+            2,
+        );
+        layer.remove_useless_vertices();
+        let mut mesh = polyanya::Mesh {
+            layers: vec![layer],
+            ..Default::default()
+        };
+        mesh.bake();
+        let mut navmesh = NavMesh::from_polyanya_mesh(mesh);
+        navmesh.set_layer_areas(0, areas);
+        let (from, to) = (vec2(1.0, 1.0), vec2(9.0, 1.0));
+        // Costs are ignored until weighted paths are enabled
+        let straight = navmesh.mesh.path(from, to).unwrap();
+        assert_eq!(navmesh.path(from, to), Some(straight));
+        navmesh.set_weighted_paths(true);
+        let cost_at = |point: Vec2| navmesh.polygon_cost(navmesh.locate(point).unwrap());
+        assert_eq!(cost_at(vec2(5.0, 3.0)), 10.0);
+        assert_eq!(cost_at(vec2(5.0, 8.5)), 0.5);
+        assert_eq!(cost_at(vec2(5.0, 7.5)), 1.0);
+
+        let straight = navmesh.mesh.path(from, to).unwrap();
+        assert!(straight.path.iter().all(|p| p.y < 7.0));
+        let path = navmesh.path(from, to).unwrap();
+        assert_eq!(path.path.last(), Some(&to));
+        assert!(path.path.iter().any(|p| p.y > 7.0));
+        assert!(path.length > straight.length);
+        let mut previous = from;
+        for step in &path.path {
+            assert!(navmesh.raycast(previous, *step).is_clear());
+            previous = *step;
+        }
+
+        // Not worth going around for a short trip
+        let path = navmesh.path(vec2(5.0, 6.5), vec2(5.0, 7.5)).unwrap();
+        assert_eq!(path.path, vec![vec2(5.0, 7.5)]);
+
+        // Points slightly out of the mesh are moved inside
+        let path = navmesh.path(vec2(1.0, -0.01), vec2(9.0, 10.01)).unwrap();
+        assert!(path.path.last().unwrap().distance(vec2(9.0, 10.0)) < 0.01);
+    }
+
+    #[test]
+    fn filter_flags() {
+        const WATER: u32 = 2;
+        let layer = Triangulation::from_outer_edges(&[
+            vec2(0.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(0.0, 10.0),
+        ])
+        .as_layer();
+        let lake = vec![
+            vec2(3.0, 0.0),
+            vec2(7.0, 0.0),
+            vec2(7.0, 8.0),
+            vec2(3.0, 8.0),
+        ];
+        let (mut layer, areas) = cut_areas(
+            layer,
+            &[(
+                lake,
+                NavMeshArea {
+                    flags: WATER,
+                    ..Default::default()
+                },
+            )],
+            1,
+        );
+        layer.remove_useless_vertices();
+        let mut navmesh = NavMesh::from_polyanya_mesh(polyanya::Mesh {
+            layers: vec![layer],
+            ..Default::default()
+        });
+        navmesh.set_layer_areas(0, areas);
+        let lake_polygon = navmesh.locate(vec2(5.0, 4.0)).unwrap();
+        assert_eq!(navmesh.polygon_flags(lake_polygon), WATER);
+
+        let (from, to) = (vec2(1.0, 1.0), vec2(9.0, 1.0));
+        let boat = navmesh.path(from, to).unwrap();
+        assert_eq!(boat.path, vec![to]);
+        let land = AreaFilter {
+            exclude: WATER,
+            ..Default::default()
+        };
+        let walk = navmesh.path_filtered(from, to, &land).unwrap();
+        assert_eq!(walk.path.last(), Some(&to));
+        assert!(walk.path.iter().any(|p| p.y >= 8.0));
+        assert!(navmesh.path_filtered(from, vec2(5.0, 4.0), &land).is_none());
+        let only_water = AreaFilter {
+            include: WATER,
+            ..Default::default()
+        };
+        assert!(navmesh.path_filtered(from, to, &only_water).is_none());
+        assert!(
+            navmesh
+                .path_filtered(vec2(4.0, 1.0), vec2(6.0, 7.0), &only_water)
+                .is_some()
+        );
+    }
+}
@@ -1,8 +1,12 @@
-//! Asset loaders that can load a [`NavMesh`] from a file
+//! Asset loaders that can load a [`NavMesh`] from a file, and savers that can write it back
 
-use std::{error::Error, fmt::Display};
+use std::{error::Error, fmt::Display, io::Write};
 
-use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::asset::{
+    AssetLoader, AsyncWriteExt, LoadContext,
+    io::{Reader, Writer},
+    saver::{AssetSaver, SavedAsset},
+};
 use polyanya::PolyanyaFile;
 
 use crate::NavMesh;
@@ -14,6 +18,9 @@ pub enum NavMeshLoaderError {
     Io(std::io::Error),
     /// Error converting to a mesh
     MeshError(polyanya::MeshError),
+    /// Error reading a serialized mesh
+    #[cfg(feature = "serialize")]
+    Deserialize(crate::NavMeshDeserializeError),
 }
 
 impl Display for NavMeshLoaderError {
@@ -21,6 +28,10 @@ impl Display for NavMeshLoaderError {
         match self {
             NavMeshLoaderError::Io(io_error) => write!(f, "IO error: {io_error}"),
             NavMeshLoaderError::MeshError(mesh_error) => write!(f, "Mesh error: {mesh_error}"),
+            #[cfg(feature = "serialize")]
+            NavMeshLoaderError::Deserialize(deserialize_error) => {
+                write!(f, "Deserialize error: {deserialize_error}")
+            }
         }
     }
 }
@@ -30,6 +41,8 @@ impl Error for NavMeshLoaderError {
         match self {
             NavMeshLoaderError::Io(io_error) => Some(io_error),
             NavMeshLoaderError::MeshError(mesh_error) => Some(mesh_error),
+            #[cfg(feature = "serialize")]
+            NavMeshLoaderError::Deserialize(deserialize_error) => Some(deserialize_error),
         }
     }
 }
@@ -67,3 +80,190 @@ impl AssetLoader for NavMeshPolyanyaLoader {
         &["polyanya.mesh"]
     }
 }
+
+/// Asset loader for a complete [`NavMesh`] serialized with [`NavMesh::to_ron`] (with a `.navmesh.ron` extension)
+/// or [`NavMesh::to_binary`] (with a `.navmesh.bin` extension).
+#[cfg(feature = "serialize")]
+#[derive(Default, Debug, Clone, Copy)]
+pub struct NavMeshSerializedLoader;
+
+#[cfg(feature = "serialize")]
+impl AssetLoader for NavMeshSerializedLoader {
+    type Asset = NavMesh;
+    type Settings = ();
+    type Error = NavMeshLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(NavMeshLoaderError::Io)?;
+        if load_context
+            .path()
+            .extension()
+            .is_some_and(|extension| extension == "ron")
+        {
+            let ron = std::str::from_utf8(&bytes).map_err(|error| {
+                NavMeshLoaderError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+            })?;
+            NavMesh::from_ron(ron).map_err(NavMeshLoaderError::Deserialize)
+        } else {
+            NavMesh::from_binary(&bytes).map_err(NavMeshLoaderError::Deserialize)
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["navmesh.ron", "navmesh.bin"]
+    }
+}
+
+impl NavMesh {
+    /// Writes this [`NavMesh`] in the `mesh 2` format, that can be read by [`NavMeshPolyanyaLoader`].
+    ///
+    /// See <https://github.com/vleue/polyanya/blob/main/meshes/format.txt> for format description.
+    ///
+    /// This format only supports a single layer, an error of kind [`std::io::ErrorKind::InvalidInput`] is
+    /// returned for meshes with several layers. The offset of the layer is applied to its vertices.
+    pub fn write_polyanya(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        let layer = match self.mesh.layers.as_slice() {
+            [layer] => layer,
+            [] => &Default::default(),
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "the mesh 2 format only supports a single layer",
+                ));
+            }
+        };
+        let index = |polygon: &u32| {
+            if *polygon == u32::MAX {
+                "-1".to_string()
+            } else {
+                polygon.to_string()
+            }
+        };
+
+        writeln!(writer, "mesh")?;
+        writeln!(writer, "2")?;
+        writeln!(writer, "{} {}", layer.vertices.len(), layer.polygons.len())?;
+        for vertex in &layer.vertices {
+            let coords = vertex.coords + layer.offset;
+            write!(
+                writer,
+                "{} {} {}",
+                coords.x,
+                coords.y,
+                vertex.polygons.len()
+            )?;
+            for polygon in &vertex.polygons {
+                write!(writer, " {}", index(polygon))?;
+            }
+            writeln!(writer)?;
+        }
+        for (polygon_index, polygon) in layer.polygons.iter().enumerate() {
+            let count = polygon.vertices.len();
+            write!(writer, "{count}")?;
+            for vertex in &polygon.vertices {
+                write!(writer, " {vertex}")?;
+            }
+            // The n-th neighbour is on the other side of the edge ending at the n-th vertex
+            for edge in 0..count {
+                let polygon = crate::topology::neighbour(
+                    &self.mesh,
+                    crate::PolygonRef {
+                        layer: 0,
+                        polygon: polygon_index as u32,
+                    },
+                    (edge + count - 1) % count,
+                )
+                .map(|polygon| polygon.polygon)
+                .unwrap_or(u32::MAX);
+                write!(writer, " {}", index(&polygon))?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Asset saver for a mesh in the `mesh 2` format, to be loaded by [`NavMeshPolyanyaLoader`].
+///
+/// See [`NavMesh::write_polyanya`] for the limitations of this format.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct NavMeshPolyanyaSaver;
+
+impl AssetSaver for NavMeshPolyanyaSaver {
+    type Asset = NavMesh;
+    type Settings = ();
+    type OutputLoader = NavMeshPolyanyaLoader;
+    type Error = std::io::Error;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, Self::Asset>,
+        _settings: &(),
+    ) -> Result<(), Self::Error> {
+        let mut bytes = Vec::new();
+        asset.get().write_polyanya(&mut bytes)?;
+        writer.write_all(&bytes).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::{ErasedLoadedAsset, LoadedAsset},
+        math::vec2,
+        tasks::block_on,
+    };
+
+    use super::*;
+    use crate::tests::square_with_hole;
+
+    #[test]
+    fn polyanya_round_trip() {
+        let original = include_bytes!("../assets/arena-merged.polyanya.mesh");
+        let navmesh =
+            NavMesh::from_polyanya_mesh(PolyanyaFile::from_bytes(original).try_into().unwrap());
+
+        let mut written = vec![];
+        navmesh.write_polyanya(&mut written).unwrap();
+        assert_eq!(
+            String::from_utf8(written.clone()).unwrap(),
+            String::from_utf8_lossy(original)
+        );
+
+        let reloaded =
+            NavMesh::from_polyanya_mesh(PolyanyaFile::from_bytes(&written).try_into().unwrap());
+        for (from, to) in [
+            (vec2(3.0, 3.0), vec2(45.0, 45.0)),
+            (vec2(10.0, 20.0), vec2(40.0, 5.0)),
+        ] {
+            assert_eq!(navmesh.path(from, to), reloaded.path(from, to));
+        }
+    }
+
+    #[test]
+    fn saver_writes_polyanya() {
+        let navmesh = square_with_hole();
+        let mut expected = vec![];
+        navmesh.write_polyanya(&mut expected).unwrap();
+
+        let loaded: ErasedLoadedAsset = LoadedAsset::from(navmesh).into();
+        let mut saved = vec![];
+        block_on(NavMeshPolyanyaSaver.save(
+            &mut saved,
+            SavedAsset::from_loaded(&loaded).unwrap(),
+            &(),
+        ))
+        .unwrap();
+        assert_eq!(saved, expected);
+    }
+}
@@ -0,0 +1,251 @@
+use std::sync::{Arc, RwLock};
+
+use bevy::{
+    asset::{AssetId, Assets},
+    math::{Vec2, Vec3},
+    platform::collections::HashMap,
+    prelude::{
+        Commands, Component, DetectChanges, Entity, Has, Query, Ref, Res, ResMut, Resource, Without,
+    },
+    tasks::AsyncComputeTaskPool,
+};
+use polyanya::Path;
+use rayon::prelude::*;
+
+use crate::{NavMesh, TransformedPath};
+
+impl NavMesh {
+    /// Finds the shortest paths between many pairs of points, in parallel.
+    ///
+    /// Results are in the same order as the queries.
+    pub fn paths_batch(&self, queries: &[(Vec2, Vec2)]) -> Vec<Option<Path>> {
+        queries
+            .par_iter()
+            .map(|(from, to)| self.path(*from, *to))
+            .collect()
+    }
+
+    /// Finds the shortest paths between many pairs of points, in parallel.
+    ///
+    /// Inputs and results are transformed using the [`NavMesh::transform`].
+    pub fn transformed_paths_batch(
+        &self,
+        queries: &[(Vec3, Vec3)],
+    ) -> Vec<Option<TransformedPath>> {
+        queries
+            .par_iter()
+            .map(|(from, to)| self.transformed_path(*from, *to))
+            .collect()
+    }
+}
+
+/// Request a path for an entity.
+///
+/// All the requests are computed together, in parallel, in a task on the [`AsyncComputeTaskPool`] started
+/// during [`PostUpdate`](bevy::app::PostUpdate). Once computed, a [`PathQueryResult`] is added alongside this
+/// component, that is kept. The path is computed again when this component changes. Requests on a [`NavMesh`]
+/// that is not yet available are kept until it is.
+///
+/// Points are transformed using the [`NavMesh::transform`].
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct PathQuery {
+    /// The [`NavMesh`] to search in.
+    pub navmesh: AssetId<NavMesh>,
+    /// Start of the path.
+    pub from: Vec3,
+    /// Destination of the path.
+    pub to: Vec3,
+}
+
+impl PathQuery {
+    /// Request a path between two points on a [`NavMesh`].
+    pub fn new(navmesh: impl Into<AssetId<NavMesh>>, from: Vec3, to: Vec3) -> Self {
+        Self {
+            navmesh: navmesh.into(),
+            from,
+            to,
+        }
+    }
+}
+
+/// Result of a [`PathQuery`], `None` if no path was found.
+#[derive(Component, Debug)]
+pub struct PathQueryResult(pub Option<TransformedPath>);
+
+/// Marks an entity whose [`PathQuery`] is being computed.
+#[derive(Component)]
+pub(crate) struct ComputingPathQuery;
+
+type PathQueryResults = Arc<RwLock<Option<Vec<(Entity, PathQuery, Option<TransformedPath>)>>>>;
+
+/// Results of the tasks computing [`PathQuery`], until they are added to their entities.
+#[derive(Resource, Default)]
+pub(crate) struct PathQueryTasks(Vec<PathQueryResults>);
+
+/// Starts a task computing all the new or changed [`PathQuery`] of each [`NavMesh`].
+pub(crate) fn start_path_queries(
+    mut commands: Commands,
+    queries: Query<(Entity, Ref<PathQuery>, Has<PathQueryResult>), Without<ComputingPathQuery>>,
+    navmeshes: Res<Assets<NavMesh>>,
+    mut tasks: ResMut<PathQueryTasks>,
+) {
+    let mut by_navmesh = HashMap::<_, Vec<_>>::default();
+    for (entity, query, has_result) in &queries {
+        if query.is_changed() || !has_result {
+            by_navmesh
+                .entry(query.navmesh)
+                .or_default()
+                .push((entity, *query));
+        }
+    }
+
+    for (navmesh, queries) in by_navmesh {
+        let Some(navmesh) = navmeshes.get(navmesh) else {
+            continue;
+        };
+        let navmesh = navmesh.clone();
+        for (entity, _) in &queries {
+            commands.entity(*entity).insert(ComputingPathQuery);
+        }
+        let results = PathQueryResults::default();
+        let writer = results.clone();
+        AsyncComputeTaskPool::get()
+            .spawn(async move {
+                let points = queries
+                    .iter()
+                    .map(|(_, query)| (query.from, query.to))
+                    .collect::<Vec<_>>();
+                let paths = navmesh.transformed_paths_batch(&points);
+                *writer.write().unwrap() = Some(
+                    queries
+                        .into_iter()
+                        .zip(paths)
+                        .map(|((entity, query), path)| (entity, query, path))
+                        .collect(),
+                );
+            })
+            .detach();
+        tasks.0.push(results);
+    }
+}
+
+/// Adds the results of the finished tasks to their entities.
+///
+/// Results of a [`PathQuery`] that changed while it was computed are dropped, so that it's computed again.
+pub(crate) fn collect_path_queries(
+    mut commands: Commands,
+    mut tasks: ResMut<PathQueryTasks>,
+    queries: Query<&PathQuery>,
+) {
+    tasks.0.retain(|task| {
+        let Some(results) = task.write().unwrap().take() else {
+            return true;
+        };
+        for (entity, query, path) in results {
+            let Ok(mut entity_commands) = commands.get_entity(entity) else {
+                continue;
+            };
+            entity_commands.remove::<ComputingPathQuery>();
+            match queries.get(entity) {
+                Ok(current) if *current == query => {
+                    entity_commands.insert(PathQueryResult(path));
+                }
+                Ok(_) => {
+                    entity_commands.remove::<PathQueryResult>();
+                }
+                Err(_) => (),
+            }
+        }
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        MinimalPlugins,
+        app::{App, PostUpdate, PreUpdate},
+        asset::{AssetApp, AssetPlugin},
+        math::vec2,
+        prelude::With,
+    };
+
+    use super::*;
+    use crate::tests::square_with_hole;
+
+    #[test]
+    fn batch_matches_single_queries() {
+        let navmesh = square_with_hole();
+        let queries = [
+            (vec2(1.0, 1.0), vec2(9.0, 9.0)),
+            (vec2(1.0, 5.0), vec2(9.0, 5.0)),
+            (vec2(1.0, 1.0), vec2(5.0, 5.0)),
+        ];
+        let batch = navmesh.paths_batch(&queries);
+        for ((from, to), path) in queries.iter().zip(batch) {
+            assert_eq!(path, navmesh.path(*from, *to));
+        }
+    }
+
+    #[test]
+    fn path_query_components() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<NavMesh>()
+            .init_resource::<PathQueryTasks>()
+            .add_systems(PreUpdate, collect_path_queries)
+            .add_systems(PostUpdate, start_path_queries);
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<NavMesh>>()
+            .add(square_with_hole());
+        let update_until_computed = |app: &mut App| {
+            for _ in 0..1000 {
+                app.update();
+                let world = app.world_mut();
+                if world
+                    .query_filtered::<(), With<ComputingPathQuery>>()
+                    .iter(world)
+                    .next()
+                    .is_none()
+                {
+                    return;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            panic!("path queries should be computed");
+        };
+
+        let found = app
+            .world_mut()
+            .spawn(PathQuery::new(
+                &handle,
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(9.0, 9.0, 0.0),
+            ))
+            .id();
+        let not_found = app
+            .world_mut()
+            .spawn(PathQuery::new(
+                &handle,
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(5.0, 5.0, 0.0),
+            ))
+            .id();
+        update_until_computed(&mut app);
+
+        let world = app.world();
+        assert!(world.get::<PathQuery>(found).is_some());
+        assert!(world.get::<PathQueryResult>(found).unwrap().0.is_some());
+        assert!(world.get::<PathQueryResult>(not_found).unwrap().0.is_none());
+
+        // Changing the query computes the path again
+        app.world_mut().get_mut::<PathQuery>(not_found).unwrap().to = Vec3::new(9.0, 1.0, 0.0);
+        update_until_computed(&mut app);
+        let path = app.world().get::<PathQueryResult>(not_found).unwrap();
+        assert_eq!(
+            path.0.as_ref().unwrap().path.last(),
+            Some(&Vec3::new(9.0, 1.0, 0.0))
+        );
+    }
+}
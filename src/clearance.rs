@@ -0,0 +1,121 @@
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+use polyanya::Path;
+
+use crate::{NavMesh, RaycastResult, TransformedPath, topology::EPSILON};
+
+impl NavMesh {
+    /// Moves each corner of a path starting at `from` away from the obstacle it turns around, by `radius`.
+    ///
+    /// Corners are moved less if there isn't enough room, so that the path stays in the mesh.
+    pub fn offset_path_corners(&self, from: Vec2, mut path: Path, radius: f32) -> Path {
+        let mut points = vec![from];
+        points.extend(path.path.iter().copied());
+        let original = points.clone();
+        let blocked = |a: Vec2, b: Vec2| matches!(self.raycast(a, b), RaycastResult::Hit(_));
+
+        for i in 1..points.len().saturating_sub(1) {
+            let (previous, corner, next) = (points[i - 1], original[i], original[i + 1]);
+            // Obstacles are on the inner side of the turn
+            let push = ((corner - original[i - 1]).normalize_or_zero()
+                - (next - corner).normalize_or_zero())
+            .normalize_or_zero();
+            if push == Vec2::ZERO {
+                continue;
+            }
+            // Start slightly away from the corner, as it is shared by polygons on both sides of the obstacle
+            let start = corner + push * EPSILON * 10.0;
+            let mut offset = match self.raycast(start, corner + push * radius) {
+                RaycastResult::Hit(hit) => hit.point.distance(corner) - EPSILON * 10.0,
+                RaycastResult::Clear => radius,
+                RaycastResult::OutOfMesh => continue,
+            };
+            for _ in 0..4 {
+                let candidate = corner + push * offset;
+                if !blocked(previous, candidate) && !blocked(candidate, next) {
+                    points[i] = candidate;
+                    break;
+                }
+                offset /= 2.0;
+            }
+        }
+
+        let straight_length =
+            |points: &[Vec2]| points.windows(2).map(|w| w[0].distance(w[1])).sum::<f32>();
+        path.length += straight_length(&points) - straight_length(&original);
+        #[cfg(feature = "detailed-layers")]
+        for (coords, _) in path.path_with_layers.iter_mut() {
+            if let Some(index) = original.iter().skip(1).position(|p| p == coords) {
+                *coords = points[index + 1];
+            }
+        }
+        path.path = points.split_off(1);
+        path
+    }
+
+    /// Finds the shortest path between two points, keeping `radius` away from the corners of obstacles.
+    ///
+    /// See [`NavMesh::offset_path_corners`].
+    pub fn path_with_clearance(&self, from: Vec2, to: Vec2, radius: f32) -> Option<Path> {
+        self.path(from, to)
+            .map(|path| self.offset_path_corners(from, path, radius))
+    }
+
+    /// Finds the shortest path between two points, keeping `radius` away from the corners of obstacles.
+    ///
+    /// Inputs and results are transformed using the [`NavMesh::transform`], `radius` is in mesh space.
+    pub fn transformed_path_with_clearance(
+        &self,
+        from: Vec3,
+        to: Vec3,
+        radius: f32,
+    ) -> Option<TransformedPath> {
+        let inner_from = self.world_to_mesh().transform_point(from).xy();
+        let inner_to = self.world_to_mesh().transform_point(to).xy();
+        self.path_with_clearance(inner_from, inner_to, radius)
+            .map(|path| self.transform_path(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use crate::tests::square_with_hole;
+
+    #[test]
+    fn corners_away_from_obstacle() {
+        let navmesh = square_with_hole();
+        let hole = [
+            vec2(4.0, 4.0),
+            vec2(6.0, 4.0),
+            vec2(6.0, 6.0),
+            vec2(4.0, 6.0),
+        ];
+        let (from, to) = (vec2(1.0, 4.5), vec2(9.0, 4.5));
+
+        let path = navmesh.path(from, to).unwrap();
+        assert!(path.path.iter().any(|p| hole.contains(p)));
+
+        let offset = navmesh.path_with_clearance(from, to, 0.5).unwrap();
+        assert_eq!(offset.path.len(), path.path.len());
+        assert_eq!(offset.path.last(), Some(&to));
+        assert!(offset.length > path.length);
+        for corner in &offset.path {
+            assert!(hole.iter().all(|v| v.distance(*corner) > 0.49));
+        }
+        let mut previous = from;
+        for step in &offset.path {
+            assert!(navmesh.raycast(previous, *step).is_clear());
+            previous = *step;
+        }
+
+        // Not enough room between the hole and the border
+        let offset = navmesh.path_with_clearance(from, to, 10.0).unwrap();
+        for step in &offset.path {
+            assert!(navmesh.is_in_mesh(*step));
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+
+use crate::{
+    NavMesh,
+    topology::{self, EPSILON, PolygonRef},
+};
+
+/// The closest navigable point to a position in a [`NavMesh`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClosestPoint {
+    /// Position of the point in the mesh.
+    pub point: Vec2,
+    /// Polygon containing the point.
+    pub polygon: PolygonRef,
+    /// Distance between the requested position and the point.
+    pub distance: f32,
+}
+
+/// The closest navigable point to a position in a [`NavMesh`], transformed using [`NavMesh::transform`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformedClosestPoint {
+    /// Position of the point in the mesh.
+    pub point: Vec3,
+    /// Polygon containing the point.
+    pub polygon: PolygonRef,
+    /// Distance between the requested position and the point, in mesh space.
+    pub distance: f32,
+}
+
+impl NavMesh {
+    /// Finds the closest point in a navigable part of the mesh, up to `max_distance` away.
+    ///
+    /// If the point is already in the mesh, it is returned as is. Otherwise, the point returned is
+    /// moved very slightly inside its polygon so that it is recognized as part of the mesh by other queries.
+    ///
+    /// Unlike [`NavMesh::set_search_delta`], this doesn't change how other queries behave.
+    pub fn closest_point(&self, point: Vec2, max_distance: f32) -> Option<ClosestPoint> {
+        if let Some(polygon) = self.locate(point) {
+            return Some(ClosestPoint {
+                point,
+                polygon,
+                distance: 0.0,
+            });
+        }
+        self.closest_point_in(point, max_distance, topology::polygons(&self.mesh))
+    }
+
+    /// Distance up to which points out of the mesh are moved inside by path queries, from the
+    /// [`search_delta`](NavMesh::search_delta) and [`search_steps`](NavMesh::search_steps).
+    pub(crate) fn snap_distance(&self) -> f32 {
+        self.search_delta() * self.search_steps() as f32
+    }
+
+    /// Finds the closest point to `point` in one of the `polygons`, up to `max_distance` away.
+    ///
+    /// Polygons are visited by increasing distance to their bounding box, kept by the polygon finder. As a
+    /// polygon is never closer than its bounding box, the search stops at the first one too far away.
+    pub(crate) fn closest_point_in(
+        &self,
+        point: Vec2,
+        max_distance: f32,
+        polygons: impl Iterator<Item = PolygonRef>,
+    ) -> Option<ClosestPoint> {
+        let finder = self.polygon_finder();
+        let mut candidates = polygons
+            .filter_map(|polygon| {
+                let (min, max) = finder.bounds(&self.mesh, polygon)?;
+                let distance = point.clamp(min, max).distance(point);
+                (distance <= max_distance).then_some((distance, polygon))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut closest: Option<(Vec2, PolygonRef, Vec2, f32)> = None;
+        for (to_bounds, polygon) in candidates {
+            if to_bounds > closest.map(|(.., d)| d).unwrap_or(max_distance) {
+                break;
+            }
+            let coords = topology::polygon_coords(&self.mesh, polygon);
+            if coords.len() < 3 {
+                continue;
+            }
+            if topology::contains(&coords, point) {
+                return Some(ClosestPoint {
+                    point,
+                    polygon,
+                    distance: 0.0,
+                });
+            }
+            for (a, b) in coords.iter().zip(coords.iter().cycle().skip(1)) {
+                let on_edge = topology::closest_on_segment(point, *a, *b);
+                let distance = on_edge.distance(point);
+                if distance <= closest.map(|(.., d)| d).unwrap_or(max_distance) {
+                    closest = Some((on_edge, polygon, topology::centroid(&coords), distance));
+                }
+            }
+        }
+
+        closest.map(|(on_edge, polygon, centroid, distance)| {
+            let inward = centroid - on_edge;
+            ClosestPoint {
+                point: on_edge + inward.normalize_or_zero() * inward.length().min(EPSILON * 10.0),
+                polygon,
+                distance,
+            }
+        })
+    }
+
+    /// Finds the closest point in a navigable part of the mesh, up to `max_distance` away.
+    ///
+    /// Inputs and results are transformed using the [`NavMesh::transform`].
+    pub fn transformed_closest_point(
+        &self,
+        point: Vec3,
+        max_distance: f32,
+    ) -> Option<TransformedClosestPoint> {
+        let inner_point = self.world_to_mesh().transform_point(point).xy();
+        let transform = self.transform();
+        self.closest_point(inner_point, max_distance)
+            .map(|closest| TransformedClosestPoint {
+                point: transform.transform_point(closest.point.extend(0.0)),
+                polygon: closest.polygon,
+                distance: closest.distance,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use crate::tests::square_with_hole;
+
+    #[test]
+    fn snap_to_mesh() {
+        let navmesh = square_with_hole();
+
+        let inside = navmesh.closest_point(vec2(1.0, 1.0), 1.0).unwrap();
+        assert_eq!(inside.point, vec2(1.0, 1.0));
+        assert_eq!(inside.distance, 0.0);
+
+        let in_hole = navmesh.closest_point(vec2(4.5, 5.0), 1.0).unwrap();
+        assert!(in_hole.point.distance(vec2(4.0, 5.0)) < 0.01);
+        assert!((in_hole.distance - 0.5).abs() < 0.001);
+        assert!(navmesh.is_in_mesh(in_hole.point));
+
+        let outside = navmesh.closest_point(vec2(12.0, 12.0), 3.0).unwrap();
+        assert!(outside.point.distance(vec2(10.0, 10.0)) < 0.01);
+        assert!(navmesh.is_in_mesh(outside.point));
+
+        assert!(navmesh.closest_point(vec2(12.0, 12.0), 2.0).is_none());
+    }
+}
@@ -0,0 +1,341 @@
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+use polyanya::{Mesh, Path};
+
+use crate::{
+    NavMesh, TransformedPath, raycast,
+    topology::{EPSILON, PolygonRef},
+};
+
+/// How a path goes from a polygon of a [`Corridor`] to the next one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Portal {
+    /// Through an edge of both polygons.
+    ///
+    /// Vertices are in the order of the edge in the polygon being left. If both ends are the same
+    /// point, the path goes from one polygon to the other through a vertex shared with another layer.
+    Edge([Vec2; 2]),
+    /// Through an off mesh link, entered at `from` in the polygon being left and exited at `to` in the
+    /// next polygon.
+    Link {
+        /// Where the link is entered.
+        from: Vec2,
+        /// Where the link is exited.
+        to: Vec2,
+    },
+}
+
+/// How a path goes from a polygon of a [`TransformedCorridor`] to the next one, transformed using
+/// [`NavMesh::transform`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransformedPortal {
+    /// Through an edge of both polygons.
+    ///
+    /// Vertices are in the order of the edge in the polygon being left. If both ends are the same
+    /// point, the path goes from one polygon to the other through a vertex shared with another layer.
+    Edge([Vec3; 2]),
+    /// Through an off mesh link, entered at `from` in the polygon being left and exited at `to` in the
+    /// next polygon.
+    Link {
+        /// Where the link is entered.
+        from: Vec3,
+        /// Where the link is exited.
+        to: Vec3,
+    },
+}
+
+/// The polygons crossed by a path, and the portals between them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Corridor {
+    /// Polygons crossed by the path, in order.
+    pub polygons: Vec<PolygonRef>,
+    /// Portals crossed between two consecutive polygons, so there is one less portal than polygons.
+    pub portals: Vec<Portal>,
+}
+
+/// The polygons crossed by a path, and the portals between them, transformed using [`NavMesh::transform`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransformedCorridor {
+    /// Polygons crossed by the path, in order.
+    pub polygons: Vec<PolygonRef>,
+    /// Portals crossed between two consecutive polygons, so there is one less portal than polygons.
+    pub portals: Vec<TransformedPortal>,
+}
+
+/// Edge shared by two polygons of the same layer, in the order of `from`.
+fn shared_edge(mesh: &Mesh, from: PolygonRef, to: PolygonRef) -> Option<[Vec2; 2]> {
+    if from.layer != to.layer {
+        return None;
+    }
+    let layer = mesh.layers.get(from.layer as usize)?;
+    let from_vertices = &layer.polygons.get(from.polygon as usize)?.vertices;
+    let to_vertices = &layer.polygons.get(to.polygon as usize)?.vertices;
+    from_vertices
+        .iter()
+        .zip(from_vertices.iter().cycle().skip(1))
+        .find(|(a, b)| to_vertices.contains(a) && to_vertices.contains(b))
+        .map(|(a, b)| {
+            [
+                layer.vertices[*a as usize].coords + layer.offset,
+                layer.vertices[*b as usize].coords + layer.offset,
+            ]
+        })
+}
+
+/// Polygons around `point`, a vertex of both `from` and `to`, between them.
+///
+/// Goes around the vertex on the side without obstacles. Returns `None` if both polygons are not
+/// in the same layer, or if the vertex is blocked on both sides.
+fn vertex_fan(
+    mesh: &Mesh,
+    from: PolygonRef,
+    to: PolygonRef,
+    point: Vec2,
+) -> Option<Vec<PolygonRef>> {
+    if from.layer != to.layer {
+        return None;
+    }
+    let layer = mesh.layers.get(from.layer as usize)?;
+    let vertex = layer
+        .polygons
+        .get(from.polygon as usize)?
+        .vertices
+        .iter()
+        .map(|v| &layer.vertices[*v as usize])
+        .find(|v| (v.coords + layer.offset).distance(point) < EPSILON)?;
+    let around = &vertex.polygons;
+    let start = around.iter().position(|p| *p == from.as_neighbour())?;
+    let end = around.iter().position(|p| *p == to.as_neighbour())?;
+
+    let count = around.len();
+    let arc = |step: usize| {
+        let mut fan = vec![];
+        let mut index = start;
+        while index != end {
+            index = (index + step) % count;
+            let polygon = PolygonRef::from_neighbour(around[index])
+                .filter(|polygon| polygon.layer == from.layer)?;
+            fan.push(polygon);
+        }
+        Some(fan)
+    };
+    match (arc(1), arc(count - 1)) {
+        (Some(a), Some(b)) => Some(if a.len() <= b.len() { a } else { b }),
+        (a, b) => a.or(b),
+    }
+}
+
+impl Corridor {
+    fn push(&mut self, mesh: &Mesh, polygon: PolygonRef, portal: Portal) {
+        let Some(&last) = self.polygons.last() else {
+            self.polygons.push(polygon);
+            return;
+        };
+        if last == polygon {
+            return;
+        }
+        if let Portal::Edge([a, b]) = portal
+            && a.distance(b) < EPSILON
+            && let Some(fan) = vertex_fan(mesh, last, polygon, a)
+        {
+            let mut previous = last;
+            for next in fan {
+                let Some(edge) = shared_edge(mesh, previous, next) else {
+                    break;
+                };
+                self.portals.push(Portal::Edge(edge));
+                self.polygons.push(next);
+                previous = next;
+            }
+            return;
+        }
+        self.portals.push(portal);
+        self.polygons.push(polygon);
+    }
+}
+
+impl NavMesh {
+    /// Computes the corridor followed by a path starting at `from`.
+    ///
+    /// Returns `None` if `from` is not in the mesh.
+    pub fn corridor(&self, from: Vec2, path: &Path) -> Option<Corridor> {
+        let start = self.closest_point(from, self.search_delta())?;
+        let mut corridor = Corridor {
+            polygons: vec![],
+            portals: vec![],
+        };
+        corridor.push(&self.mesh, start.polygon, Portal::Edge([from, from]));
+
+        let mut current = start.polygon;
+        let mut segment_start = start.point;
+        for step in &path.path {
+            if self.is_link(segment_start, *step)
+                && let Some(polygon) = self.locate(*step)
+            {
+                corridor.push(
+                    &self.mesh,
+                    polygon,
+                    Portal::Link {
+                        from: segment_start,
+                        to: *step,
+                    },
+                );
+                current = polygon;
+                segment_start = *step;
+                continue;
+            }
+            let walk = raycast::walk(&self.mesh, current, segment_start, *step);
+            for (polygon, portal) in walk.polygons.iter().skip(1).zip(walk.portals) {
+                corridor.push(&self.mesh, *polygon, Portal::Edge(portal));
+            }
+            current = *walk.polygons.last().unwrap_or(&current);
+            if walk.hit.is_some() {
+                // Path going slightly out of the mesh, continue from where it comes back
+                if let Some(polygon) = self.locate(*step) {
+                    corridor.push(&self.mesh, polygon, Portal::Edge([*step, *step]));
+                    current = polygon;
+                }
+            }
+            segment_start = *step;
+        }
+        Some(corridor)
+    }
+
+    /// If a path step from `from` to `to` goes through an off mesh link.
+    fn is_link(&self, from: Vec2, to: Vec2) -> bool {
+        self.links.iter().any(|link| {
+            (link.start.distance(from) < EPSILON && link.end.distance(to) < EPSILON)
+                || (link.bidirectional
+                    && link.end.distance(from) < EPSILON
+                    && link.start.distance(to) < EPSILON)
+        })
+    }
+
+    /// Finds the shortest path between two points, with the corridor it follows.
+    pub fn path_with_corridor(&self, from: Vec2, to: Vec2) -> Option<(Path, Corridor)> {
+        let path = self.path(from, to)?;
+        let corridor = self.corridor(from, &path)?;
+        Some((path, corridor))
+    }
+
+    /// Finds the shortest path between two points, with the corridor it follows.
+    ///
+    /// Inputs and results are transformed using the [`NavMesh::transform`].
+    pub fn transformed_path_with_corridor(
+        &self,
+        from: Vec3,
+        to: Vec3,
+    ) -> Option<(TransformedPath, TransformedCorridor)> {
+        let world_to_mesh = self.world_to_mesh();
+        let inner_from = world_to_mesh.transform_point(from).xy();
+        let inner_to = world_to_mesh.transform_point(to).xy();
+        let (path, corridor) = self.path_with_corridor(inner_from, inner_to)?;
+        let transform = self.transform();
+        Some((
+            self.transform_path(path),
+            TransformedCorridor {
+                polygons: corridor.polygons,
+                portals: corridor
+                    .portals
+                    .into_iter()
+                    .map(|portal| {
+                        let transform_point = |v: Vec2| transform.transform_point(v.extend(0.0));
+                        match portal {
+                            Portal::Edge(edge) => {
+                                TransformedPortal::Edge(edge.map(transform_point))
+                            }
+                            Portal::Link { from, to } => TransformedPortal::Link {
+                                from: transform_point(from),
+                                to: transform_point(to),
+                            },
+                        }
+                    })
+                    .collect(),
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use super::*;
+    use crate::{NavMeshLink, areas::NavMeshArea, tests::square_with_hole};
+
+    #[test]
+    fn corridor_around_hole() {
+        let navmesh = square_with_hole();
+
+        for (from, to) in [
+            (vec2(1.0, 5.0), vec2(9.0, 5.0)),
+            (vec2(5.0, 1.0), vec2(5.0, 9.0)),
+            (vec2(0.5, 0.5), vec2(9.5, 9.5)),
+        ] {
+            let (_, corridor) = navmesh.path_with_corridor(from, to).unwrap();
+            assert_eq!(corridor.polygons.len(), corridor.portals.len() + 1);
+            assert_eq!(corridor.polygons.first(), navmesh.polygon_at(from).as_ref());
+            assert!(
+                navmesh
+                    .polygon(*corridor.polygons.last().unwrap())
+                    .unwrap()
+                    .contains(to)
+            );
+            for (polygons, portal) in corridor.polygons.windows(2).zip(&corridor.portals) {
+                let Portal::Edge(portal) = portal else {
+                    panic!("there are no links");
+                };
+                assert!(portal[0].distance(portal[1]) > EPSILON);
+                for polygon in polygons {
+                    let polygon = navmesh.polygon(*polygon).unwrap();
+                    assert!(polygon.contains(portal[0]) && polygon.contains(portal[1]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn corridor_through_link() {
+        let mut navmesh = crate::links::tests::two_islands();
+        navmesh.set_links(vec![NavMeshLink {
+            start: vec2(8.0, 5.0),
+            end: vec2(12.0, 5.0),
+            cost: 1.0,
+            bidirectional: false,
+            flags: NavMeshArea::DEFAULT_FLAGS,
+        }]);
+        let (from, to) = (vec2(1.0, 5.0), vec2(19.0, 5.0));
+        let (_, corridor) = navmesh.path_with_corridor(from, to).unwrap();
+        assert_eq!(corridor.polygons.len(), corridor.portals.len() + 1);
+
+        let links = corridor
+            .portals
+            .iter()
+            .enumerate()
+            .filter(|(_, portal)| matches!(portal, Portal::Link { .. }))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            links,
+            vec![(
+                links[0].0,
+                &Portal::Link {
+                    from: vec2(8.0, 5.0),
+                    to: vec2(12.0, 5.0)
+                }
+            )]
+        );
+        // The link goes from a polygon on one side of the wall to a polygon on the other side
+        let index = links[0].0;
+        let polygon = |index: usize| navmesh.polygon(corridor.polygons[index]).unwrap();
+        assert!(polygon(index).contains(vec2(8.0, 5.0)));
+        assert!(polygon(index + 1).contains(vec2(12.0, 5.0)));
+        assert!(polygon(corridor.polygons.len() - 1).contains(to));
+        for portal in &corridor.portals {
+            if let Portal::Edge([a, b]) = portal {
+                assert!(a.distance(*b) > EPSILON);
+            }
+        }
+    }
+}
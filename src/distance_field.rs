@@ -0,0 +1,337 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+
+use crate::{
+    AreaFilter, NavMesh,
+    topology::{self, PolygonRef},
+};
+
+/// Travel distance from a set of sources to every polygon of a [`NavMesh`].
+///
+/// Distances are measured along the polygon graph, entering each polygon at the point of its edge
+/// closest to where the previous polygon was entered, and going through the off mesh links of the
+/// [`NavMesh`] from their start to their end. They are approximate: the path they follow doesn't go
+/// straight around corners, so they can be longer than the shortest path, even for a polygon in line
+/// of sight of a source. Use [`NavMesh::path`] when the exact length is needed.
+///
+/// When [weighted paths](NavMesh::set_weighted_paths) are enabled, the distance traveled in a polygon is multiplied
+/// by its traversal cost, like for [`NavMesh::path`]. Polygons behind closed gates can't be reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceField {
+    distances: Vec<Vec<f32>>,
+}
+
+impl DistanceField {
+    /// Distance to a polygon, or `None` if it can't be reached from any source.
+    pub fn distance(&self, polygon: PolygonRef) -> Option<f32> {
+        self.distances
+            .get(polygon.layer as usize)
+            .and_then(|layer| layer.get(polygon.polygon as usize))
+            .copied()
+            .filter(|distance| distance.is_finite())
+    }
+
+    /// Distances to every polygon of a layer, indexed by polygon.
+    ///
+    /// Polygons that can't be reached have a distance of [`f32::INFINITY`].
+    pub fn layer(&self, layer: u8) -> &[f32] {
+        self.distances
+            .get(layer as usize)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Iterates over all the polygons that can be reached, with their distance.
+    pub fn iter(&self) -> impl Iterator<Item = (PolygonRef, f32)> + '_ {
+        self.distances
+            .iter()
+            .enumerate()
+            .flat_map(|(layer, distances)| {
+                distances
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, distance)| distance.is_finite())
+                    .map(move |(polygon, distance)| {
+                        (
+                            PolygonRef {
+                                layer: layer as u8,
+                                polygon: polygon as u32,
+                            },
+                            *distance,
+                        )
+                    })
+            })
+    }
+}
+
+struct Node {
+    distance: f32,
+    polygon: PolygonRef,
+    point: Vec2,
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Node {}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed to make the `BinaryHeap` a min-heap
+        other.distance.total_cmp(&self.distance)
+    }
+}
+
+impl NavMesh {
+    /// Computes the travel distance from the closest of `sources` to every polygon of the mesh.
+    ///
+    /// Sources that are not in the mesh are ignored.
+    pub fn distance_field(&self, sources: &[Vec2]) -> DistanceField {
+        self.distance_field_on_layers(sources, 0..self.mesh.layers.len() as u8)
+    }
+
+    /// Computes the travel distance from the closest of `sources` to every polygon of the mesh,
+    /// only going through the polygons of `layers`.
+    ///
+    /// Sources that are not in the mesh or not in one of the `layers` are ignored.
+    pub fn distance_field_on_layers(
+        &self,
+        sources: &[Vec2],
+        layers: impl IntoIterator<Item = u8>,
+    ) -> DistanceField {
+        let layers = layers.into_iter().collect::<HashSet<_>>();
+        let filter = AreaFilter {
+            exclude: self.closed_gates,
+            ..Default::default()
+        };
+        let allowed = |polygon: PolygonRef| {
+            layers.contains(&polygon.layer) && self.filtered_cost(polygon, &filter).is_finite()
+        };
+        // Links that can be entered from each polygon: where they start, the polygon and point where
+        // they end, and their cost
+        let mut links: HashMap<PolygonRef, Vec<(Vec2, PolygonRef, Vec2, f32)>> = HashMap::new();
+        for link in self.links.iter().filter(|link| filter.allows(link.flags)) {
+            let (Some(start), Some(end)) = (self.locate(link.start), self.locate(link.end)) else {
+                continue;
+            };
+            links
+                .entry(start)
+                .or_default()
+                .push((link.start, end, link.end, link.cost.max(0.0)));
+            if link.bidirectional {
+                links.entry(end).or_default().push((
+                    link.end,
+                    start,
+                    link.start,
+                    link.cost.max(0.0),
+                ));
+            }
+        }
+        let mut distances = self
+            .mesh
+            .layers
+            .iter()
+            .map(|layer| vec![f32::INFINITY; layer.polygons.len()])
+            .collect::<Vec<_>>();
+
+        let mut to_visit = sources
+            .iter()
+            .filter_map(|source| {
+                self.locate(*source).map(|polygon| Node {
+                    distance: 0.0,
+                    polygon,
+                    point: *source,
+                })
+            })
+            .filter(|node| allowed(node.polygon))
+            .collect::<BinaryHeap<_>>();
+        for node in &to_visit {
+            distances[node.polygon.layer as usize][node.polygon.polygon as usize] = 0.0;
+        }
+
+        while let Some(Node {
+            distance,
+            polygon,
+            point,
+        }) = to_visit.pop()
+        {
+            if distance > distances[polygon.layer as usize][polygon.polygon as usize] {
+                continue;
+            }
+            let cost = self.filtered_cost(polygon, &filter);
+            let coords = topology::polygon_coords(&self.mesh, polygon);
+            let through_edges = coords
+                .iter()
+                .zip(coords.iter().cycle().skip(1))
+                .enumerate()
+                .filter_map(|(edge, (a, b))| {
+                    let next = topology::neighbour(&self.mesh, polygon, edge)?;
+                    let entry = topology::closest_on_segment(point, *a, *b);
+                    Some((next, entry, distance + point.distance(entry) * cost))
+                });
+            let through_links =
+                links
+                    .get(&polygon)
+                    .into_iter()
+                    .flatten()
+                    .map(|(start, next, entry, link_cost)| {
+                        (
+                            *next,
+                            *entry,
+                            distance + point.distance(*start) * cost + link_cost,
+                        )
+                    });
+            for (next, entry, next_distance) in through_edges.chain(through_links) {
+                if !allowed(next) {
+                    continue;
+                }
+                let known = &mut distances[next.layer as usize][next.polygon as usize];
+                if next_distance < *known {
+                    *known = next_distance;
+                    to_visit.push(Node {
+                        distance: next_distance,
+                        polygon: next,
+                        point: entry,
+                    });
+                }
+            }
+        }
+
+        DistanceField { distances }
+    }
+
+    /// Computes the travel distance from the closest of `sources` to every polygon of the mesh.
+    ///
+    /// Sources are transformed using the [`NavMesh::transform`], distances are in mesh space.
+    pub fn transformed_distance_field(&self, sources: &[Vec3]) -> DistanceField {
+        let world_to_mesh = self.world_to_mesh();
+        let inner_sources = sources
+            .iter()
+            .map(|source| world_to_mesh.transform_point(*source).xy())
+            .collect::<Vec<_>>();
+        self.distance_field(&inner_sources)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+    use polyanya::{Mesh, Polygon, Vertex};
+
+    use super::*;
+    use crate::{NavMeshLink, areas::NavMeshArea};
+
+    #[test]
+    fn exact_distances() {
+        // Three unit squares in a row
+        let navmesh = NavMesh::from_polyanya_mesh(
+            Mesh::new(
+                vec![
+                    Vertex::new(vec2(0.0, 0.0), vec![0, u32::MAX]),
+                    Vertex::new(vec2(1.0, 0.0), vec![1, 0, u32::MAX]),
+                    Vertex::new(vec2(2.0, 0.0), vec![2, 1, u32::MAX]),
+                    Vertex::new(vec2(3.0, 0.0), vec![2, u32::MAX]),
+                    Vertex::new(vec2(3.0, 1.0), vec![2, u32::MAX]),
+                    Vertex::new(vec2(2.0, 1.0), vec![1, 2, u32::MAX]),
+                    Vertex::new(vec2(1.0, 1.0), vec![0, 1, u32::MAX]),
+                    Vertex::new(vec2(0.0, 1.0), vec![0, u32::MAX]),
+                ],
+                vec![
+                    Polygon::new(vec![0, 1, 6, 7], false),
+                    Polygon::new(vec![1, 2, 5, 6], false),
+                    Polygon::new(vec![2, 3, 4, 5], false),
+                ],
+            )
+            .unwrap(),
+        );
+        let field = navmesh.distance_field(&[vec2(0.25, 0.5)]);
+        assert_eq!(field.layer(0), &[0.0, 0.75, 1.75]);
+
+        let field = navmesh.distance_field(&[vec2(0.25, 0.5), vec2(2.5, 0.5)]);
+        assert_eq!(field.layer(0), &[0.0, 0.5, 0.0]);
+
+        let field = navmesh.distance_field_on_layers(&[vec2(0.25, 0.5)], [0]);
+        assert_eq!(field.layer(0), &[0.0, 0.75, 1.75]);
+
+        let field = navmesh.distance_field_on_layers(&[vec2(0.25, 0.5)], [1]);
+        assert_eq!(field.iter().count(), 0);
+    }
+
+    #[test]
+    fn distances_around_wall() {
+        let navmesh = NavMesh::from_edge_and_obstacles(
+            vec![
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(10.0, 10.0),
+                vec2(0.0, 10.0),
+            ],
+            vec![vec![
+                vec2(4.5, 0.0),
+                vec2(5.5, 0.0),
+                vec2(5.5, 8.0),
+                vec2(4.5, 8.0),
+            ]],
+        );
+        let field = navmesh.distance_field(&[vec2(1.0, 1.0)]);
+
+        let polygon_at = |point| navmesh.locate(point).unwrap();
+        assert_eq!(field.distance(polygon_at(vec2(1.0, 1.0))), Some(0.0));
+        let near = field.distance(polygon_at(vec2(3.0, 4.0))).unwrap();
+        let far = field.distance(polygon_at(vec2(8.0, 1.0))).unwrap();
+        // The other side of the wall is reached after going around its top, (4.5, 8.0)
+        assert!(far >= vec2(1.0, 1.0).distance(vec2(4.5, 8.0)) - 0.001);
+        assert!(near < far);
+        assert_eq!(field.iter().count(), navmesh.mesh.layers[0].polygons.len());
+
+        let empty = navmesh.distance_field(&[vec2(20.0, 20.0)]);
+        assert_eq!(empty.iter().count(), 0);
+    }
+
+    #[test]
+    fn distances_through_link() {
+        let mut navmesh = crate::links::tests::two_islands();
+        let source = vec2(1.0, 1.0);
+        let island = navmesh.locate(vec2(19.0, 1.0)).unwrap();
+        assert_eq!(navmesh.distance_field(&[source]).distance(island), None);
+
+        navmesh.set_links(vec![NavMeshLink {
+            start: vec2(8.0, 1.0),
+            end: vec2(12.0, 1.0),
+            cost: 5.0,
+            bidirectional: false,
+            flags: NavMeshArea::DEFAULT_FLAGS,
+        }]);
+        let field = navmesh.distance_field(&[source]);
+        let distance = field.distance(island).unwrap();
+        // Walking to the link, going through it, then reaching the polygon
+        assert!(distance >= source.distance(vec2(8.0, 1.0)) + 5.0);
+        assert!(
+            distance
+                <= source.distance(vec2(8.0, 1.0))
+                    + 5.0
+                    + vec2(12.0, 1.0).distance(vec2(19.0, 1.0))
+        );
+        assert_eq!(field.iter().count(), navmesh.get().layers[0].polygons.len());
+
+        // The link can't be used from its end
+        let back = navmesh.distance_field(&[vec2(19.0, 1.0)]);
+        assert_eq!(back.distance(navmesh.locate(source).unwrap()), None);
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+
+use crate::{
+    AreaFilter, NavMesh,
+    topology::{self, PolygonRef},
+};
+
+impl NavMesh {
+    /// Closes the gates with these flags.
+    ///
+    /// Gates don't have their own ids: they share the flags of [`NavMeshArea`](crate::prelude::NavMeshArea) and
+    /// [`OffMeshLink`](crate::prelude::OffMeshLink), and a gate is any polygon or link with one of these flags.
+    /// Polygons and links with one of the closed flags can't be crossed by any path, until the gates are opened
+    /// again. To close a single gate, give it a flag that no other area or link uses, for example one bit per
+    /// door. This doesn't rebuild the [`NavMesh`], and is kept when it's updated by the
+    /// [`NavmeshUpdaterPlugin`](crate::prelude::NavmeshUpdaterPlugin).
+    ///
+    /// To open a gate only for some agents, keep it open and exclude its flags with the
+    /// [`AreaFilter`] of the others.
+    ///
+    /// Gates are also closed on the variants for other agent radii. This clears the path cache if a gate was open.
+    pub fn close_gates(&mut self, flags: u32) {
+        if self.closed_gates | flags != self.closed_gates {
+            self.closed_gates |= flags;
+            self.link_graphs = Default::default();
+            self.clear_path_cache();
+        }
+        for variant in &mut self.variants {
+            variant.close_gates(flags);
+        }
+    }
+
+    /// Opens the gates with these flags, closed by [`NavMesh::close_gates`].
+    ///
+    /// Gates are also opened on the variants for other agent radii. This clears the path cache if a gate was closed.
+    pub fn open_gates(&mut self, flags: u32) {
+        if self.closed_gates & flags != 0 {
+            self.closed_gates &= !flags;
+            self.link_graphs = Default::default();
+            self.clear_path_cache();
+        }
+        for variant in &mut self.variants {
+            variant.open_gates(flags);
+        }
+    }
+
+    /// Flags of the gates currently closed.
+    pub fn closed_gates(&self) -> u32 {
+        self.closed_gates
+    }
+
+    /// Polygons that can be reached from `start` without crossing closed gates, walking and going
+    /// through off mesh links.
+    pub(crate) fn reachable_polygons(&self, start: PolygonRef) -> Vec<PolygonRef> {
+        let filter = AreaFilter {
+            exclude: self.closed_gates,
+            ..Default::default()
+        };
+        let allowed = |polygon: PolygonRef| filter.allows(self.polygon_flags(polygon));
+        let jumps = self
+            .links
+            .iter()
+            .filter(|link| filter.allows(link.flags))
+            .flat_map(|link| {
+                let (start, end) = (self.locate(link.start), self.locate(link.end));
+                [
+                    start.zip(end),
+                    end.zip(start).filter(|_| link.bidirectional),
+                ]
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let mut reached = HashSet::from([start]);
+        let mut to_visit = vec![start];
+        while let Some(polygon) = to_visit.pop() {
+            if !allowed(polygon) {
+                continue;
+            }
+            let edges = topology::polygon_coords(&self.mesh, polygon).len();
+            let neighbours = (0..edges)
+                .filter_map(|edge| topology::neighbour(&self.mesh, polygon, edge))
+                .chain(
+                    jumps
+                        .iter()
+                        .filter(|(from, _)| *from == polygon)
+                        .map(|(_, to)| *to),
+                )
+                .collect::<Vec<_>>();
+            for next in neighbours {
+                if allowed(next) && reached.insert(next) {
+                    to_visit.push(next);
+                }
+            }
+        }
+        let mut reached = reached.into_iter().collect::<Vec<_>>();
+        reached.sort_unstable();
+        reached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use crate::{NavMeshLink, PathCacheSettings, areas::NavMeshArea};
+
+    use super::*;
+
+    const GATE: u32 = 1 << 1;
+
+    /// A room with a wall in the middle, that can be crossed through a gate at the top.
+    fn walled() -> NavMesh {
+        let mut navmesh = NavMesh::from_edge_and_obstacles(
+            vec![
+                vec2(0.0, 0.0),
+                vec2(20.0, 0.0),
+                vec2(20.0, 10.0),
+                vec2(0.0, 10.0),
+            ],
+            vec![vec![
+                vec2(9.0, -1.0),
+                vec2(11.0, -1.0),
+                vec2(11.0, 8.0),
+                vec2(9.0, 8.0),
+            ]],
+        );
+        let mesh = navmesh.get();
+        let areas = topology::polygons(&mesh)
+            .map(|polygon| {
+                let center = topology::centroid(&topology::polygon_coords(&mesh, polygon));
+                if center.x > 9.0 && center.x < 11.0 {
+                    NavMeshArea {
+                        flags: GATE,
+                        ..Default::default()
+                    }
+                } else {
+                    NavMeshArea::default()
+                }
+            })
+            .collect();
+        navmesh.set_layer_areas(0, areas);
+        navmesh
+    }
+
+    #[test]
+    fn toggle_gate() {
+        let mut navmesh = walled();
+        navmesh.set_path_cache(Some(PathCacheSettings::default()));
+        let (from, to) = (vec2(1.0, 1.0), vec2(19.0, 1.0));
+        let open = navmesh.path(from, to).unwrap();
+
+        navmesh.close_gates(GATE);
+        assert_eq!(navmesh.closed_gates(), GATE);
+        assert!(navmesh.path(from, to).is_none());
+        assert!(
+            navmesh
+                .path_filtered(from, to, &AreaFilter::default())
+                .is_none()
+        );
+
+        navmesh.open_gates(GATE);
+        assert_eq!(navmesh.closed_gates(), 0);
+        assert_eq!(navmesh.path(from, to), Some(open));
+
+        // A closed gate away from the path doesn't change it
+        navmesh.close_gates(GATE);
+        let (near, far) = (vec2(1.0, 9.0), vec2(8.0, 1.0));
+        assert_eq!(navmesh.path(near, far), navmesh.get().path(near, far));
+
+        // The closest point on this side of the gate is reached instead
+        let partial = navmesh.path_to_closest(from, to).unwrap();
+        assert!(partial.is_partial);
+        assert!(partial.path.path.last().unwrap().x <= 9.0 + 0.01);
+        navmesh.open_gates(GATE);
+
+        // Only agents of another team are stopped by the gate
+        let enemies = AreaFilter {
+            exclude: GATE,
+            ..Default::default()
+        };
+        assert!(navmesh.path_filtered(from, to, &enemies).is_none());
+    }
+
+    #[test]
+    fn gate_on_link() {
+        let mut navmesh = walled();
+        navmesh.close_gates(GATE);
+        navmesh.set_links(vec![NavMeshLink {
+            start: vec2(8.0, 1.0),
+            end: vec2(12.0, 1.0),
+            cost: 0.0,
+            bidirectional: true,
+            flags: 1 << 2,
+        }]);
+        let (from, to) = (vec2(1.0, 1.0), vec2(19.0, 1.0));
+        assert!(navmesh.path(from, to).is_some());
+
+        navmesh.close_gates(1 << 2);
+        assert!(navmesh.path(from, to).is_none());
+    }
+}
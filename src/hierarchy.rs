@@ -0,0 +1,396 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+use polyanya::{Mesh, Path};
+
+use crate::{
+    NavMesh, TransformedPath,
+    topology::{self, PolygonRef},
+};
+
+/// Default number of polygons in a cluster.
+pub(crate) const DEFAULT_CLUSTER_SIZE: usize = 64;
+
+/// A coarse graph over the polygons of a mesh, grouped in clusters of neighbouring polygons.
+#[derive(Debug, Clone)]
+pub(crate) struct Hierarchy {
+    /// Cluster of each polygon, per layer.
+    clusters: Vec<Vec<usize>>,
+    /// Representative point of each cluster.
+    centers: Vec<Vec2>,
+    /// For each cluster, the neighbouring clusters and the point used to go from one to the other.
+    links: Vec<HashMap<usize, Vec2>>,
+}
+
+impl Hierarchy {
+    pub(crate) fn new(mesh: &Mesh, cluster_size: usize) -> Self {
+        let mut clusters = mesh
+            .layers
+            .iter()
+            .map(|layer| vec![usize::MAX; layer.polygons.len()])
+            .collect::<Vec<_>>();
+        let mut members: Vec<Vec<PolygonRef>> = vec![];
+
+        // Grow clusters from each polygon not yet assigned, breadth first
+        for seed in topology::polygons(mesh) {
+            if clusters[seed.layer as usize][seed.polygon as usize] != usize::MAX {
+                continue;
+            }
+            let cluster = members.len();
+            let mut cluster_members = vec![];
+            let mut to_visit = VecDeque::from([seed]);
+            clusters[seed.layer as usize][seed.polygon as usize] = cluster;
+            while let Some(polygon) = to_visit.pop_front() {
+                cluster_members.push(polygon);
+                let edges = topology::polygon_coords(mesh, polygon).len();
+                for edge in 0..edges {
+                    if cluster_members.len() + to_visit.len() >= cluster_size.max(1) {
+                        break;
+                    }
+                    if let Some(next) = topology::neighbour(mesh, polygon, edge)
+                        && let Some(assigned) = clusters
+                            .get_mut(next.layer as usize)
+                            .and_then(|layer| layer.get_mut(next.polygon as usize))
+                        && *assigned == usize::MAX
+                    {
+                        *assigned = cluster;
+                        to_visit.push_back(next);
+                    }
+                }
+            }
+            members.push(cluster_members);
+        }
+
+        let centers = members
+            .iter()
+            .map(|polygons| {
+                let centroids = polygons
+                    .iter()
+                    .map(|p| topology::centroid(&topology::polygon_coords(mesh, *p)))
+                    .collect::<Vec<_>>();
+                let average = centroids.iter().sum::<Vec2>() / centroids.len() as f32;
+                centroids
+                    .into_iter()
+                    .min_by(|a, b| a.distance(average).total_cmp(&b.distance(average)))
+                    .unwrap_or(average)
+            })
+            .collect::<Vec<_>>();
+
+        let mut links = vec![HashMap::new(); members.len()];
+        for polygon in topology::polygons(mesh) {
+            let from = clusters[polygon.layer as usize][polygon.polygon as usize];
+            let coords = topology::polygon_coords(mesh, polygon);
+            for (edge, (a, b)) in coords.iter().zip(coords.iter().cycle().skip(1)).enumerate() {
+                let Some(next) = topology::neighbour(mesh, polygon, edge) else {
+                    continue;
+                };
+                let Some(to) = clusters
+                    .get(next.layer as usize)
+                    .and_then(|layer| layer.get(next.polygon as usize))
+                    .copied()
+                    .filter(|to| *to != from && *to != usize::MAX)
+                else {
+                    continue;
+                };
+                let middle = (*a + *b) / 2.0;
+                let cost =
+                    |point: Vec2| centers[from].distance(point) + point.distance(centers[to]);
+                let current = links[from].entry(to).or_insert(middle);
+                if cost(middle) < cost(*current) {
+                    *current = middle;
+                }
+            }
+        }
+
+        Hierarchy {
+            clusters,
+            centers,
+            links,
+        }
+    }
+
+    fn cluster(&self, polygon: PolygonRef) -> Option<usize> {
+        self.clusters
+            .get(polygon.layer as usize)
+            .and_then(|layer| layer.get(polygon.polygon as usize))
+            .copied()
+    }
+
+    /// Points to go through from `from` to `to`, one between each pair of clusters crossed.
+    fn waypoints(&self, start: usize, goal: usize, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        let position = |cluster: usize| match cluster {
+            c if c == start => from,
+            c if c == goal => to,
+            c => self.centers[c],
+        };
+        let mut best = HashMap::from([(start, 0.0)]);
+        let mut came_from: HashMap<usize, (usize, Vec2)> = HashMap::new();
+        let mut to_visit = BinaryHeap::from([Node {
+            estimate: from.distance(to),
+            cluster: start,
+        }]);
+
+        while let Some(Node { cluster, .. }) = to_visit.pop() {
+            if cluster == goal {
+                let mut waypoints = vec![];
+                let mut current = goal;
+                while let Some((previous, point)) = came_from.get(&current) {
+                    waypoints.push(*point);
+                    current = *previous;
+                }
+                waypoints.reverse();
+                return Some(waypoints);
+            }
+            let cost = best[&cluster];
+            for (next, point) in &self.links[cluster] {
+                let next_cost =
+                    cost + position(cluster).distance(*point) + point.distance(position(*next));
+                if best.get(next).is_none_or(|known| next_cost < *known) {
+                    best.insert(*next, next_cost);
+                    came_from.insert(*next, (cluster, *point));
+                    to_visit.push(Node {
+                        estimate: next_cost + position(*next).distance(to),
+                        cluster: *next,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+struct Node {
+    estimate: f32,
+    cluster: usize,
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Node {}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed to make the `BinaryHeap` a min-heap
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl NavMesh {
+    /// Sets the number of polygons grouped in each cluster for [`NavMesh::hierarchical_path`]. The default value is `64`.
+    pub fn set_hierarchy_cluster_size(&mut self, cluster_size: usize) {
+        self.hierarchy_cluster_size = cluster_size;
+        self.hierarchy = Default::default();
+    }
+
+    fn hierarchy(&self) -> &Hierarchy {
+        self.hierarchy
+            .get_or_init(|| Hierarchy::new(&self.mesh, self.hierarchy_cluster_size))
+    }
+
+    /// Finds a path between two points, first on a coarse graph of clusters of polygons, then
+    /// refining it locally.
+    ///
+    /// The path found may be longer than the shortest one, but not longer than going along the shortest
+    /// path with a detour to each point where it goes from a cluster to the next one. The graph of clusters
+    /// is built the first time it's needed, then kept with the mesh.
+    ///
+    /// On meshes with a single layer, the refined path is shortened by skipping steps that are in line of sight.
+    /// The graph of clusters doesn't know about traversal costs, gates or off mesh links: on a mesh with
+    /// weighted paths enabled or links, or while some gates are closed, this is the same as [`NavMesh::path`].
+    pub fn hierarchical_path(&self, from: Vec2, to: Vec2) -> Option<Path> {
+        if self.closed_gates != 0 || self.has_costs() || !self.links.is_empty() {
+            return self.path(from, to);
+        }
+        let (Some(start), Some(goal)) = (self.locate(from), self.locate(to)) else {
+            return self.path(from, to);
+        };
+        let hierarchy = self.hierarchy();
+        let (Some(start), Some(goal)) = (hierarchy.cluster(start), hierarchy.cluster(goal)) else {
+            return self.path(from, to);
+        };
+        if start == goal || hierarchy.links[start].contains_key(&goal) {
+            return self.path(from, to);
+        }
+        if !self.are_connected(from, to) {
+            return None;
+        }
+        let Some(waypoints) = hierarchy.waypoints(start, goal, from, to) else {
+            return self.path(from, to);
+        };
+
+        let mut path = Path {
+            length: 0.0,
+            path: vec![],
+            #[cfg(feature = "detailed-layers")]
+            path_with_layers: vec![],
+        };
+        let mut previous = from;
+        for waypoint in waypoints.into_iter().chain(std::iter::once(to)) {
+            let Some(step) = self.mesh.path(previous, waypoint) else {
+                return self.path(from, to);
+            };
+            path.length += step.length;
+            path.path.extend(step.path);
+            #[cfg(feature = "detailed-layers")]
+            path.path_with_layers.extend(step.path_with_layers);
+            previous = waypoint;
+        }
+        path.path.dedup();
+
+        if self.mesh.layers.len() == 1 {
+            self.shorten(from, &mut path);
+        }
+        Some(path)
+    }
+
+    /// Removes steps of a path when the next ones can be reached in a straight line.
+    ///
+    /// Only for meshes with a single layer: the shortened path doesn't keep track of the layers it goes through.
+    fn shorten(&self, from: Vec2, path: &mut Path) {
+        let mut shortened = vec![];
+        let mut current = from;
+        let mut index = 0;
+        while index < path.path.len() {
+            let furthest = (index..path.path.len())
+                .rev()
+                .find(|i| self.raycast(current, path.path[*i]).is_clear())
+                .unwrap_or(index);
+            current = path.path[furthest];
+            shortened.push(current);
+            index = furthest + 1;
+        }
+        path.length = std::iter::once(from)
+            .chain(shortened.iter().copied())
+            .zip(shortened.iter())
+            .map(|(a, b)| a.distance(*b))
+            .sum();
+        #[cfg(feature = "detailed-layers")]
+        {
+            path.path_with_layers = shortened.iter().map(|p| (*p, 0)).collect();
+        }
+        path.path = shortened;
+    }
+
+    /// Finds a path between two points, first on a coarse graph of clusters of polygons, then
+    /// refining it locally.
+    ///
+    /// Inputs and results are transformed using the [`NavMesh::transform`].
+    pub fn transformed_hierarchical_path(&self, from: Vec3, to: Vec3) -> Option<TransformedPath> {
+        let inner_from = self.world_to_mesh().transform_point(from).xy();
+        let inner_to = self.world_to_mesh().transform_point(to).xy();
+        self.hierarchical_path(inner_from, inner_to)
+            .map(|path| self.transform_path(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use super::*;
+
+    /// Length of a path from `from` going along `shortest`, with a detour from its closest point to each
+    /// of the `waypoints`, in order.
+    ///
+    /// A path through the waypoints can't be longer: from each waypoint to the next one, it's at most
+    /// the walk back to `shortest`, along it, then to the next waypoint.
+    fn detour_bound(navmesh: &NavMesh, from: Vec2, shortest: &Path, waypoints: &[Vec2]) -> f32 {
+        let steps = std::iter::once(from)
+            .chain(shortest.path.iter().copied())
+            .collect::<Vec<_>>();
+        let mut along = vec![0.0];
+        let mut detours = 0.0;
+        for waypoint in waypoints {
+            let mut start = 0.0;
+            let (closest, at) = steps
+                .windows(2)
+                .map(|segment| {
+                    let closest = topology::closest_on_segment(*waypoint, segment[0], segment[1]);
+                    let at = start + segment[0].distance(closest);
+                    start += segment[0].distance(segment[1]);
+                    (closest, at)
+                })
+                .min_by(|a, b| a.0.distance(*waypoint).total_cmp(&b.0.distance(*waypoint)))
+                .unwrap();
+            detours += 2.0
+                * navmesh
+                    .path(*waypoint, closest)
+                    .map_or(0.0, |path| path.length);
+            along.push(at);
+        }
+        along.push(shortest.length);
+        along.windows(2).map(|a| (a[1] - a[0]).abs()).sum::<f32>() + detours
+    }
+
+    #[test]
+    fn close_to_shortest_path() {
+        // A grid of small obstacles, to get a lot of polygons
+        let obstacles = (0..10)
+            .flat_map(|x| (0..10).map(move |y| vec2(x as f32 * 10.0 + 5.0, y as f32 * 10.0 + 5.0)))
+            .map(|center| {
+                vec![
+                    center + vec2(-2.0, -2.0),
+                    center + vec2(2.0, -2.0),
+                    center + vec2(2.0, 2.0),
+                    center + vec2(-2.0, 2.0),
+                ]
+            })
+            .collect();
+        let mut navmesh = NavMesh::from_edge_and_obstacles(
+            vec![
+                vec2(0.0, 0.0),
+                vec2(100.0, 0.0),
+                vec2(100.0, 100.0),
+                vec2(0.0, 100.0),
+            ],
+            obstacles,
+        );
+        navmesh.set_hierarchy_cluster_size(8);
+        assert!(navmesh.hierarchy().centers.len() > 10);
+
+        for (from, to) in [
+            (vec2(1.0, 1.0), vec2(99.0, 99.0)),
+            (vec2(1.0, 50.0), vec2(99.0, 51.0)),
+            (vec2(30.0, 9.0), vec2(61.0, 92.0)),
+        ] {
+            let shortest = navmesh.path(from, to).unwrap();
+            let path = navmesh.hierarchical_path(from, to).unwrap();
+            assert_eq!(path.path.last(), Some(&to));
+            assert!(path.length >= shortest.length - 0.001);
+            let hierarchy = navmesh.hierarchy();
+            let cluster = |point| hierarchy.cluster(navmesh.locate(point).unwrap()).unwrap();
+            let waypoints = hierarchy
+                .waypoints(cluster(from), cluster(to), from, to)
+                .unwrap();
+            assert!(!waypoints.is_empty());
+            assert!(path.length <= detour_bound(&navmesh, from, &shortest, &waypoints) + 0.001);
+            let mut previous = from;
+            for step in &path.path {
+                assert!(navmesh.raycast(previous, *step).is_clear());
+                previous = *step;
+            }
+        }
+
+        assert!(
+            navmesh
+                .hierarchical_path(vec2(1.0, 1.0), vec2(5.0, 5.0))
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+use polyanya::Mesh;
+
+use crate::{
+    AreaFilter, NavMesh,
+    topology::{self, PolygonRef},
+};
+
+/// Connected components of a [`NavMesh`].
+///
+/// Two polygons are in the same island if a path exists between them, following stitches between layers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Islands {
+    islands: Vec<Vec<u32>>,
+    polygons: Vec<Vec<PolygonRef>>,
+}
+
+impl Islands {
+    pub(crate) fn new(mesh: &Mesh) -> Self {
+        let offsets = mesh
+            .layers
+            .iter()
+            .scan(0, |offset, layer| {
+                let start = *offset;
+                *offset += layer.polygons.len();
+                Some(start)
+            })
+            .collect::<Vec<_>>();
+        let flat = |polygon: PolygonRef| offsets[polygon.layer as usize] + polygon.polygon as usize;
+
+        let mut parents =
+            (0..mesh.layers.iter().map(|l| l.polygons.len()).sum()).collect::<Vec<_>>();
+        fn root(parents: &mut [usize], mut node: usize) -> usize {
+            while parents[node] != node {
+                parents[node] = parents[parents[node]];
+                node = parents[node];
+            }
+            node
+        }
+
+        for polygon in topology::polygons(mesh) {
+            let edges = mesh.layers[polygon.layer as usize].polygons[polygon.polygon as usize]
+                .vertices
+                .len();
+            for edge in 0..edges {
+                if let Some(next) = topology::neighbour(mesh, polygon, edge)
+                    && (next.layer as usize) < offsets.len()
+                {
+                    let (a, b) = (
+                        root(&mut parents, flat(polygon)),
+                        root(&mut parents, flat(next)),
+                    );
+                    parents[a.max(b)] = a.min(b);
+                }
+            }
+        }
+
+        let mut labels = vec![u32::MAX; parents.len()];
+        let mut polygons: Vec<Vec<PolygonRef>> = vec![];
+        let islands = mesh
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(layer, layer_polygons)| {
+                (0..layer_polygons.polygons.len())
+                    .map(|polygon| {
+                        let root = root(&mut parents, offsets[layer] + polygon);
+                        if labels[root] == u32::MAX {
+                            labels[root] = polygons.len() as u32;
+                            polygons.push(vec![]);
+                        }
+                        polygons[labels[root] as usize].push(PolygonRef {
+                            layer: layer as u8,
+                            polygon: polygon as u32,
+                        });
+                        labels[root]
+                    })
+                    .collect()
+            })
+            .collect();
+        Islands { islands, polygons }
+    }
+
+    /// Island of a polygon, or `None` if the polygon doesn't exist.
+    pub fn island(&self, polygon: PolygonRef) -> Option<u32> {
+        self.islands
+            .get(polygon.layer as usize)
+            .and_then(|layer| layer.get(polygon.polygon as usize))
+            .copied()
+    }
+
+    /// Number of islands in the mesh.
+    pub fn count(&self) -> u32 {
+        self.polygons.len() as u32
+    }
+
+    /// Iterates over the polygons of an island.
+    pub fn polygons(&self, island: u32) -> impl Iterator<Item = PolygonRef> + '_ {
+        self.polygons
+            .get(island as usize)
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+}
+
+impl NavMesh {
+    /// Connected components of the mesh.
+    ///
+    /// They are computed the first time they are needed, then kept with the mesh.
+    pub fn islands(&self) -> &Islands {
+        self.islands.get_or_init(|| Islands::new(&self.mesh))
+    }
+
+    /// Checks if a path exists between two points, without searching for it.
+    ///
+    /// Off mesh links that are not behind closed gates are followed from one island to another.
+    /// Returns `false` if a point is not in the mesh.
+    pub fn are_connected(&self, a: Vec2, b: Vec2) -> bool {
+        let (Some(a), Some(b)) = (self.locate(a), self.locate(b)) else {
+            return false;
+        };
+        let islands = self.islands();
+        let (Some(a), Some(b)) = (islands.island(a), islands.island(b)) else {
+            return false;
+        };
+        a == b
+            || (!self.links.is_empty()
+                && self
+                    .reachable_islands(
+                        a,
+                        &AreaFilter {
+                            exclude: self.closed_gates,
+                            ..Default::default()
+                        },
+                    )
+                    .contains(&b))
+    }
+
+    /// Checks if a path exists between two points, without searching for it.
+    ///
+    /// Inputs are transformed using the [`NavMesh::transform`].
+    pub fn transformed_are_connected(&self, a: Vec3, b: Vec3) -> bool {
+        let world_to_mesh = self.world_to_mesh();
+        self.are_connected(
+            world_to_mesh.transform_point(a).xy(),
+            world_to_mesh.transform_point(b).xy(),
+        )
+    }
+
+    /// Returns `false` if both points are known to be in different islands.
+    ///
+    /// Points not found in the mesh are left for the path search to handle with its search delta.
+    pub(crate) fn may_be_connected(&self, a: Vec2, b: Vec2) -> bool {
+        // Polyanya already checks islands on meshes with a single layer
+        if self.mesh.layers.len() < 2 {
+            return true;
+        }
+        match (self.locate(a), self.locate(b)) {
+            (Some(a), Some(b)) => {
+                let islands = self.islands();
+                islands.island(a) == islands.island(b)
+            }
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+    use polyanya::{Polygon, Vertex};
+
+    use super::*;
+
+    #[test]
+    fn separated_squares() {
+        // Two squares that don't touch, each split in two triangles
+        let navmesh = NavMesh::from_polyanya_mesh(
+            Mesh::new(
+                vec![
+                    Vertex::new(vec2(0.0, 0.0), vec![0, u32::MAX]),
+                    Vertex::new(vec2(1.0, 0.0), vec![0, u32::MAX]),
+                    Vertex::new(vec2(1.0, 1.0), vec![1, 0, u32::MAX]),
+                    Vertex::new(vec2(0.0, 1.0), vec![1, u32::MAX, 0]),
+                    Vertex::new(vec2(2.0, 0.0), vec![2, u32::MAX]),
+                    Vertex::new(vec2(3.0, 0.0), vec![2, u32::MAX]),
+                    Vertex::new(vec2(3.0, 1.0), vec![3, 2, u32::MAX]),
+                    Vertex::new(vec2(2.0, 1.0), vec![3, u32::MAX, 2]),
+                ],
+                vec![
+                    Polygon::new(vec![0, 1, 2], false),
+                    Polygon::new(vec![0, 2, 3], false),
+                    Polygon::new(vec![4, 5, 6], false),
+                    Polygon::new(vec![4, 6, 7], false),
+                ],
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(navmesh.islands().count(), 2);
+        assert_eq!(navmesh.islands().polygons(0).count(), 2);
+        assert!(navmesh.are_connected(vec2(0.2, 0.1), vec2(0.2, 0.9)));
+        assert!(!navmesh.are_connected(vec2(0.2, 0.1), vec2(2.2, 0.1)));
+        assert!(!navmesh.are_connected(vec2(0.2, 0.1), vec2(1.5, 0.5)));
+    }
+
+    #[test]
+    fn stitched_layers() {
+        // Two 10 by 10 squares side by side, the second one in a layer offset by `(10.0, 0.0)`
+        let squares = |stitched: bool| {
+            let square = |offset: Vec2| {
+                let mut layer = polyanya::Triangulation::from_outer_edges(&[
+                    vec2(0.0, 0.0),
+                    vec2(10.0, 0.0),
+                    vec2(10.0, 10.0),
+                    vec2(0.0, 10.0),
+                ])
+                .as_layer();
+                layer.offset = offset;
+                layer
+            };
+            let mut mesh = Mesh {
+                layers: vec![square(Vec2::ZERO), square(vec2(10.0, 0.0))],
+                ..Default::default()
+            };
+            // Stitching also marks the layer in the indices of neighbour polygons, so it's done
+            // even without points to join the layers
+            let points = if stitched {
+                vec![((0, 1), vec![vec2(10.0, 0.0), vec2(10.0, 10.0)])]
+            } else {
+                vec![]
+            };
+            mesh.stitch_at_points(points, false);
+            NavMesh::from_polyanya_mesh(mesh)
+        };
+
+        let navmesh = squares(true);
+        assert_eq!(navmesh.islands().count(), 1);
+        assert_eq!(
+            navmesh.islands().polygons(0).count(),
+            navmesh
+                .get()
+                .layers
+                .iter()
+                .map(|l| l.polygons.len())
+                .sum::<usize>()
+        );
+        assert!(navmesh.are_connected(vec2(1.0, 1.0), vec2(18.0, 5.0)));
+
+        let navmesh = squares(false);
+        assert_eq!(navmesh.islands().count(), 2);
+        assert!(navmesh.are_connected(vec2(11.0, 1.0), vec2(18.0, 5.0)));
+        assert!(!navmesh.are_connected(vec2(1.0, 1.0), vec2(18.0, 5.0)));
+    }
+}
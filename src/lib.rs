@@ -12,7 +12,10 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
 
 #[cfg(feature = "debug-with-gizmos")]
 use bevy::{
@@ -22,7 +25,7 @@ use bevy::{
     prelude::{Component, Gizmos, Query, Res, Resource},
 };
 use bevy::{
-    app::{App, Plugin},
+    app::{App, Plugin, PostUpdate, PreUpdate},
     asset::{Asset, AssetApp},
     log::{debug, warn},
     math::{Affine3A, Quat, Vec2, Vec3, Vec3Swizzles},
@@ -36,17 +39,66 @@ use bevy::{
 };
 use itertools::Itertools;
 
+mod areas;
 pub mod asset_loaders;
+mod batch;
+mod clearance;
+mod closest_point;
+mod corridor;
+mod distance_field;
+mod gates;
+mod hierarchy;
+mod islands;
+mod links;
+mod mesh_export;
 mod obstacles;
+mod partial_path;
+mod path_cache;
+mod path_to_any;
+mod polygons;
+mod raster;
+mod raycast;
+mod sampling;
+#[cfg(feature = "serialize")]
+mod serialization;
+mod svg;
+#[cfg(feature = "bevy_tiled_map")]
+mod tiled_map;
+mod topology;
 mod updater;
+mod variants;
+
+pub use areas::AreaFilter;
+pub use batch::{PathQuery, PathQueryResult};
+pub use closest_point::{ClosestPoint, TransformedClosestPoint};
+pub use corridor::{Corridor, Portal, TransformedCorridor, TransformedPortal};
+pub use distance_field::DistanceField;
+pub use islands::Islands;
+pub use links::NavMeshLink;
+pub use mesh_export::{MeshColors, MeshExportSettings};
+pub use partial_path::PartialPath;
+pub use path_cache::PathCacheSettings;
+pub use polygons::NavMeshPolygon;
+pub use raster::NavMeshImageSettings;
+pub use raycast::{RaycastHit, RaycastResult, TransformedRaycastHit};
+#[cfg(feature = "serialize")]
+pub use serialization::NavMeshDeserializeError;
+pub use svg::SvgOptions;
+pub use topology::PolygonRef;
 
 /// Prelude for imports
 pub mod prelude {
+    pub use crate::areas::{AreaFilter, NavMeshArea};
+    pub use crate::links::OffMeshLink;
     #[cfg(feature = "parry2d")]
     pub use crate::obstacles::parry2d::shape::SharedShapeStorage;
     pub use crate::obstacles::{
         ObstacleSource, cached::CachedObstacle, primitive::PrimitiveObstacle,
     };
+    #[cfg(feature = "bevy_tiled_map")]
+    pub use crate::tiled_map::{
+        TiledNavMesh, TiledNavMeshPlugin, TiledNavMeshSettings, TiledObstaclesBackend,
+    };
     pub use crate::updater::{
         CachableObstacle, FilterObstaclesMode, ManagedNavMesh, NAVMESH_BUILD_DURATION,
         NavMeshSettings, NavMeshStatus, NavMeshUpdateMode, NavMeshUpdateModeBlocking,
@@ -86,7 +138,13 @@ pub struct NavMeshDebug(
 impl Plugin for VleueNavigatorPlugin {
     fn build(&self, app: &mut App) {
         app.register_asset_loader(asset_loaders::NavMeshPolyanyaLoader)
-            .init_asset::<NavMesh>();
+            .init_asset::<NavMesh>()
+            .init_resource::<batch::PathQueryTasks>()
+            .add_systems(PreUpdate, batch::collect_path_queries)
+            .add_systems(PostUpdate, batch::start_path_queries);
+
+        #[cfg(feature = "serialize")]
+        app.register_asset_loader(asset_loaders::NavMeshSerializedLoader);
 
         #[cfg(feature = "debug-with-gizmos")]
         app.add_systems(Update, display_navmesh);
@@ -110,10 +168,54 @@ pub struct TransformedPath {
 use polyanya::Trimesh;
 pub use polyanya::{Path, Triangulation};
 
+/// Error that can happen while creating a [`NavMesh`] from a Bevy [`Mesh`]
+#[derive(Debug, Clone, Copy)]
+pub enum NavMeshFromMeshError {
+    /// The [`Mesh`] doesn't use a topology made of triangles
+    UnsupportedTopology(PrimitiveTopology),
+    /// The [`Mesh`] doesn't have a [`Mesh::ATTRIBUTE_POSITION`]
+    MissingPositions,
+    /// The positions of the [`Mesh`] are not in a 2D or 3D float format
+    UnsupportedPositionFormat,
+    /// An index of the [`Mesh`] refers to a vertex that doesn't exist
+    IndexOutOfBounds(usize),
+    /// Error converting to a mesh
+    MeshError(polyanya::MeshError),
+}
+
+impl std::fmt::Display for NavMeshFromMeshError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NavMeshFromMeshError::UnsupportedTopology(topology) => {
+                write!(f, "Unsupported topology: {topology:?}")
+            }
+            NavMeshFromMeshError::MissingPositions => write!(f, "Missing vertex positions"),
+            NavMeshFromMeshError::UnsupportedPositionFormat => {
+                write!(f, "Unsupported vertex position format")
+            }
+            NavMeshFromMeshError::IndexOutOfBounds(index) => {
+                write!(f, "Index out of bounds: {index}")
+            }
+            NavMeshFromMeshError::MeshError(mesh_error) => write!(f, "Mesh error: {mesh_error}"),
+        }
+    }
+}
+
+impl std::error::Error for NavMeshFromMeshError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NavMeshFromMeshError::MeshError(mesh_error) => Some(mesh_error),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct BuildingMesh {
     pub(crate) mesh: polyanya::Mesh,
     pub(crate) failed_stitches: Vec<(u8, u8)>,
+    pub(crate) areas: Vec<Vec<areas::NavMeshArea>>,
+    pub(crate) links_by_layer: Vec<Vec<NavMeshLink>>,
 }
 
 /// A navigation mesh
@@ -122,6 +224,20 @@ pub struct NavMesh {
     mesh: Arc<polyanya::Mesh>,
     building: Option<BuildingMesh>,
     transform: Transform,
+    islands: OnceLock<Islands>,
+    polygon_finder: OnceLock<topology::PolygonFinder>,
+    path_cache: Option<path_cache::PathCache>,
+    hierarchy: OnceLock<hierarchy::Hierarchy>,
+    hierarchy_cluster_size: usize,
+    area_table: OnceLock<sampling::AreaTable>,
+    areas: Vec<Vec<areas::NavMeshArea>>,
+    cheapest_cost: f32,
+    weighted_paths: bool,
+    links: Vec<NavMeshLink>,
+    links_by_layer: Vec<Vec<NavMeshLink>>,
+    link_graphs: links::LinkGraphs,
+    closed_gates: u32,
+    variants: Vec<NavMesh>,
 }
 
 impl NavMesh {
@@ -131,6 +247,20 @@ impl NavMesh {
             mesh: Arc::new(mesh),
             building: None,
             transform: Transform::IDENTITY,
+            islands: OnceLock::new(),
+            polygon_finder: OnceLock::new(),
+            path_cache: None,
+            hierarchy: OnceLock::new(),
+            hierarchy_cluster_size: hierarchy::DEFAULT_CLUSTER_SIZE,
+            area_table: OnceLock::new(),
+            areas: vec![],
+            cheapest_cost: 1.0,
+            weighted_paths: false,
+            links: vec![],
+            links_by_layer: vec![],
+            link_graphs: Default::default(),
+            closed_gates: 0,
+            variants: vec![],
         }
     }
 
@@ -138,13 +268,40 @@ impl NavMesh {
     /// All triangle normals are aligned during the conversion, so the orientation of the [`Mesh`] does not matter.
     /// The [`polyanya::Mesh`] generated in the process can be modified via `callback`.
     ///
-    /// Only supports meshes with the [`PrimitiveTopology::TriangleList`].
+    /// Returns `None` if the [`Mesh`] can't be converted, see [`NavMesh::try_from_bevy_mesh_and_then`] for details.
     pub fn from_bevy_mesh_and_then(
         mesh: &Mesh,
         callback: impl Fn(&mut polyanya::Mesh),
     ) -> Option<NavMesh> {
-        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
-            return None;
+        Self::try_from_bevy_mesh_and_then(mesh, callback).ok()
+    }
+
+    /// Creates a [`NavMesh`] from a Bevy [`Mesh`], assuming it constructs a 2D structure.
+    /// All triangle normals are aligned during the conversion, so the orientation of the [`Mesh`] does not matter.
+    ///
+    /// Returns `None` if the [`Mesh`] can't be converted, see [`NavMesh::try_from_bevy_mesh`] for details.
+    pub fn from_bevy_mesh(mesh: &Mesh) -> Option<NavMesh> {
+        Self::from_bevy_mesh_and_then(mesh, |_| {})
+    }
+
+    /// Creates a [`NavMesh`] from a Bevy [`Mesh`], assuming it constructs a 2D structure.
+    /// All triangle normals are aligned during the conversion, so the orientation of the [`Mesh`] does not matter.
+    /// The [`polyanya::Mesh`] generated in the process can be modified via `callback`.
+    ///
+    /// Supports meshes with the [`PrimitiveTopology::TriangleList`] or [`PrimitiveTopology::TriangleStrip`],
+    /// with or without indices, and with positions as [`VertexAttributeValues::Float32x3`] or
+    /// [`VertexAttributeValues::Float32x2`]. Vertices at the same position are merged, and triangles
+    /// without an area are ignored.
+    pub fn try_from_bevy_mesh_and_then(
+        mesh: &Mesh,
+        callback: impl Fn(&mut polyanya::Mesh),
+    ) -> Result<NavMesh, NavMeshFromMeshError> {
+        let topology = mesh.primitive_topology();
+        if !matches!(
+            topology,
+            PrimitiveTopology::TriangleList | PrimitiveTopology::TriangleStrip
+        ) {
+            return Err(NavMeshFromMeshError::UnsupportedTopology(topology));
         }
         let normal = get_vectors(mesh, Mesh::ATTRIBUTE_NORMAL)
             .and_then(|mut i| i.next())
@@ -152,18 +309,70 @@ impl NavMesh {
         let rotation = Quat::from_rotation_arc(normal, Vec3::Z);
         let rotation_reverse = rotation.inverse();
 
-        let vertices = get_vectors(mesh, Mesh::ATTRIBUTE_POSITION)
-            .expect("can't extract a navmesh from a mesh without `Mesh::ATTRIBUTE_POSITION`")
-            .map(|vertex| rotation_reverse.mul_vec3(vertex))
-            .map(|coords| coords.xy())
+        let positions: Vec<Vec3> = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(values)) => {
+                values.iter().cloned().map(Vec3::from).collect()
+            }
+            Some(VertexAttributeValues::Float32x2(values)) => {
+                values.iter().map(|[x, y]| Vec3::new(*x, *y, 0.0)).collect()
+            }
+            Some(_) => return Err(NavMeshFromMeshError::UnsupportedPositionFormat),
+            None => return Err(NavMeshFromMeshError::MissingPositions),
+        };
+
+        // Merge vertices at the same position, keeping the order of their first occurrence
+        let mut vertices = vec![];
+        let mut merged_positions = vec![];
+        let mut merged = HashMap::new();
+        let remap: Vec<usize> = positions
+            .into_iter()
+            .map(|vertex| {
+                let coords = rotation_reverse.mul_vec3(vertex).xy();
+                *merged
+                    .entry((coords.x.to_bits(), coords.y.to_bits()))
+                    .or_insert_with(|| {
+                        vertices.push(coords);
+                        merged_positions.push(vertex);
+                        vertices.len() - 1
+                    })
+            })
             .collect();
 
-        let triangles = mesh
-            .indices()
-            .expect("No polygon indices found in mesh")
-            .iter()
-            .tuples::<(_, _, _)>()
-            .map(|(a, b, c)| [c, b, a])
+        let indices: Vec<usize> = match mesh.indices() {
+            Some(indices) => indices.iter().collect(),
+            None => (0..remap.len()).collect(),
+        };
+        let indices = indices
+            .into_iter()
+            .map(|index| {
+                remap
+                    .get(index)
+                    .copied()
+                    .ok_or(NavMeshFromMeshError::IndexOutOfBounds(index))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let triangles: Vec<(usize, usize, usize)> = if topology == PrimitiveTopology::TriangleList {
+            indices.into_iter().tuples().collect()
+        } else {
+            indices.into_iter().tuple_windows().collect()
+        };
+        // Triangles are reversed, triangles turning clockwise around the normal are flipped first so that
+        // they all end up in the same winding, and the ones without an area are ignored
+        let triangles = triangles
+            .into_iter()
+            .filter_map(|(a, b, c)| {
+                let winding = (merged_positions[b] - merged_positions[a])
+                    .cross(merged_positions[c] - merged_positions[a])
+                    .dot(normal);
+                if winding > 0.0 {
+                    Some([c, b, a])
+                } else if winding < 0.0 {
+                    Some([a, b, c])
+                } else {
+                    None
+                }
+            })
             .collect();
 
         let mut polyanya_mesh = Trimesh {
@@ -171,20 +380,20 @@ impl NavMesh {
             triangles,
         }
         .try_into()
-        .unwrap();
+        .map_err(NavMeshFromMeshError::MeshError)?;
         callback(&mut polyanya_mesh);
 
         let mut navmesh = Self::from_polyanya_mesh(polyanya_mesh);
         navmesh.transform = Transform::from_rotation(rotation);
-        Some(navmesh)
+        Ok(navmesh)
     }
 
     /// Creates a [`NavMesh`] from a Bevy [`Mesh`], assuming it constructs a 2D structure.
     /// All triangle normals are aligned during the conversion, so the orientation of the [`Mesh`] does not matter.
     ///
-    /// Only supports meshes with the [`PrimitiveTopology::TriangleList`].
-    pub fn from_bevy_mesh(mesh: &Mesh) -> Option<NavMesh> {
-        Self::from_bevy_mesh_and_then(mesh, |_| {})
+    /// See [`NavMesh::try_from_bevy_mesh_and_then`] for the supported meshes.
+    pub fn try_from_bevy_mesh(mesh: &Mesh) -> Result<NavMesh, NavMeshFromMeshError> {
+        Self::try_from_bevy_mesh_and_then(mesh, |_| {})
     }
 
     /// Builds a navmesh from its edges and obstacles.
@@ -222,6 +431,7 @@ impl NavMesh {
         if let Some(mesh) = Arc::get_mut(&mut self.mesh) {
             debug!("setting mesh delta to {}", delta);
             mesh.set_search_delta(delta);
+            self.clear_path_cache();
             true
         } else {
             warn!("failed setting mesh delta to {}", delta);
@@ -241,6 +451,7 @@ impl NavMesh {
         if let Some(mesh) = Arc::get_mut(&mut self.mesh) {
             debug!("setting mesh steps to {}", steps);
             mesh.set_search_steps(steps);
+            self.clear_path_cache();
             true
         } else {
             warn!("failed setting mesh steps to {}", steps);
@@ -256,7 +467,19 @@ impl NavMesh {
     /// Asynchronously finds the shortest path between two points.
     #[inline]
     pub async fn get_path(&self, from: Vec2, to: Vec2) -> Option<Path> {
-        self.mesh.get_path(from, to).await
+        let key = self.path_cache_key(from, to);
+        if let Some(cached) = self.cached_path(key, from, to) {
+            return cached;
+        }
+        let path = if !self.links.is_empty() || self.closed_gates != 0 || self.has_costs() {
+            self.uncached_path(from, to, &AreaFilter::default())
+        } else if !self.may_be_connected(from, to) {
+            None
+        } else {
+            self.mesh.get_path(from, to).await
+        };
+        self.cache_path(key, from, to, path.as_ref());
+        path
     }
 
     /// Asynchronously finds the shortest path between two points.
@@ -265,14 +488,24 @@ impl NavMesh {
     pub async fn get_transformed_path(&self, from: Vec3, to: Vec3) -> Option<TransformedPath> {
         let inner_from = self.world_to_mesh().transform_point(from).xy();
         let inner_to = self.world_to_mesh().transform_point(to).xy();
-        let path = self.mesh.get_path(inner_from, inner_to).await;
+        let path = self.get_path(inner_from, inner_to).await;
         path.map(|path| self.transform_path(path))
     }
 
     /// Finds the shortest path between two points.
+    ///
+    /// If [weighted paths](NavMesh::set_weighted_paths) are enabled and some polygons have a traversal cost, a path
+    /// close to the cheapest one is found instead. Its `length` is still the distance traveled. Off-mesh links are used when they make the path cheaper, and the cost of
+    /// each link used is added to the `length`. Closed gates are avoided.
     #[inline]
     pub fn path(&self, from: Vec2, to: Vec2) -> Option<Path> {
-        self.mesh.path(from, to)
+        let key = self.path_cache_key(from, to);
+        if let Some(cached) = self.cached_path(key, from, to) {
+            return cached;
+        }
+        let path = self.uncached_path(from, to, &AreaFilter::default());
+        self.cache_path(key, from, to, path.as_ref());
+        path
     }
 
     /// Finds the shortest path between two points.
@@ -281,7 +514,7 @@ impl NavMesh {
     pub fn transformed_path(&self, from: Vec3, to: Vec3) -> Option<TransformedPath> {
         let inner_from = self.world_to_mesh().transform_point(from).xy();
         let inner_to = self.world_to_mesh().transform_point(to).xy();
-        let path = self.mesh.path(inner_from, inner_to);
+        let path = self.path(inner_from, inner_to);
         path.map(|path| self.transform_path(path))
     }
 
@@ -331,57 +564,67 @@ impl NavMesh {
 
     /// Creates a [`Mesh`] from this [`NavMesh`], suitable for debugging the surface.
     ///
-    /// This mesh doesn't have normals.
+    /// This mesh covers all the layers, scaled with the `detailed-layers` feature and placed at their offset. It
+    /// doesn't have normals. See [`NavMesh::to_mesh_with`] for a [`Mesh`] that can be rendered.
     pub fn to_mesh(&self) -> Mesh {
         let mut new_mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all());
         let mesh_to_world = self.transform();
-        new_mesh.insert_attribute(
-            Mesh::ATTRIBUTE_POSITION,
-            self.mesh.layers[0]
-                .vertices
-                .iter()
-                .map(|v| v.coords.extend(0.0))
-                .map(|coords| mesh_to_world.transform_point(coords).into())
-                .collect::<Vec<[f32; 3]>>(),
-        );
-        new_mesh.insert_indices(Indices::U32(
-            self.mesh.layers[0]
-                .polygons
-                .iter()
-                .flat_map(|p| {
-                    (2..p.vertices.len())
-                        .flat_map(|i| [p.vertices[0], p.vertices[i - 1], p.vertices[i]])
-                })
-                .collect(),
-        ));
+        let mut positions: Vec<[f32; 3]> = vec![];
+        let mut indices: Vec<u32> = vec![];
+        for layer in &self.mesh.layers {
+            let first = positions.len() as u32;
+            positions.extend(
+                mesh_export::layer_coords(layer, true)
+                    .into_iter()
+                    .map(|coords| mesh_to_world.transform_point(coords.extend(0.0)).to_array()),
+            );
+            indices.extend(
+                layer
+                    .polygons
+                    .iter()
+                    .flat_map(|p| {
+                        (2..p.vertices.len())
+                            .flat_map(|i| [p.vertices[0], p.vertices[i - 1], p.vertices[i]])
+                    })
+                    .map(|v| first + v),
+            );
+        }
+        new_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        new_mesh.insert_indices(Indices::U32(indices));
         new_mesh
     }
 
     /// Creates a [`Mesh`] from this [`NavMesh`], showing the wireframe of the polygons.
+    ///
+    /// Like [`NavMesh::to_mesh`], this covers all the layers, scaled with the `detailed-layers` feature and placed
+    /// at their offset.
     pub fn to_wireframe_mesh(&self) -> Mesh {
         let mut new_mesh = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::all());
         let mesh_to_world = self.transform();
-        new_mesh.insert_attribute(
-            Mesh::ATTRIBUTE_POSITION,
-            self.mesh.layers[0]
-                .vertices
-                .iter()
-                .map(|v| [v.coords.x, v.coords.y, 0.0])
-                .map(|coords| mesh_to_world.transform_point(coords.into()).into())
-                .collect::<Vec<[f32; 3]>>(),
-        );
-        new_mesh.insert_indices(Indices::U32(
-            self.mesh.layers[0]
-                .polygons
-                .iter()
-                .flat_map(|p| {
-                    (0..p.vertices.len())
-                        .map(|i| [p.vertices[i], p.vertices[(i + 1) % p.vertices.len()]])
-                })
-                .unique_by(|[a, b]| if a < b { (*a, *b) } else { (*b, *a) })
-                .flatten()
-                .collect(),
-        ));
+        let mut positions: Vec<[f32; 3]> = vec![];
+        let mut indices: Vec<u32> = vec![];
+        for layer in &self.mesh.layers {
+            let first = positions.len() as u32;
+            positions.extend(
+                mesh_export::layer_coords(layer, true)
+                    .into_iter()
+                    .map(|coords| mesh_to_world.transform_point(coords.extend(0.0)).to_array()),
+            );
+            indices.extend(
+                layer
+                    .polygons
+                    .iter()
+                    .flat_map(|p| {
+                        (0..p.vertices.len())
+                            .map(|i| [p.vertices[i], p.vertices[(i + 1) % p.vertices.len()]])
+                    })
+                    .unique_by(|[a, b]| if a < b { (*a, *b) } else { (*b, *a) })
+                    .flatten()
+                    .map(|v| first + v),
+            );
+        }
+        new_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        new_mesh.insert_indices(Indices::U32(indices));
         new_mesh
     }
 
@@ -457,14 +700,32 @@ pub fn display_navmesh(
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    use bevy::math::vec2;
     use polyanya::Trimesh;
 
     use super::*;
 
+    /// A 10 by 10 square with a 2 by 2 hole in its middle, from `(4.0, 4.0)` to `(6.0, 6.0)`.
+    pub(crate) fn square_with_hole() -> NavMesh {
+        NavMesh::from_edge_and_obstacles(
+            vec![
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(10.0, 10.0),
+                vec2(0.0, 10.0),
+            ],
+            vec![vec![
+                vec2(4.0, 4.0),
+                vec2(6.0, 4.0),
+                vec2(6.0, 6.0),
+                vec2(4.0, 6.0),
+            ]],
+        )
+    }
+
     #[test]
     fn generating_from_existing_navmesh_results_in_same_navmesh() {
-        // TODO: try and find why this is in CW instead of CCW
         let expected_navmesh = NavMesh::from_polyanya_mesh(
             Trimesh {
                 vertices: vec![
@@ -547,6 +808,90 @@ mod tests {
         assert_same_navmesh(expected_navmesh, actual_navmesh);
     }
 
+    #[test]
+    fn mesh_variants_generate_same_navmesh() {
+        // A 2x1 rectangle made of two quads, with triangles not sharing their vertices
+        let corners = [
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [1.0, 1.0],
+            [0.0, 1.0],
+            [2.0, 0.0],
+            [2.0, 1.0],
+        ];
+        let triangles = [[0, 1, 2], [0, 2, 3], [1, 4, 5], [1, 5, 2]];
+        let mut list = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all());
+        // 2D positions, using the same id as `Mesh::ATTRIBUTE_POSITION`
+        list.insert_attribute(
+            bevy::render::mesh::MeshVertexAttribute::new(
+                "Vertex_Position",
+                0,
+                bevy::render::render_resource::VertexFormat::Float32x2,
+            ),
+            triangles
+                .iter()
+                .flatten()
+                .map(|i| corners[*i])
+                .collect::<Vec<[f32; 2]>>(),
+        );
+        let from_list = NavMesh::try_from_bevy_mesh(&list).unwrap();
+        assert_eq!(from_list.get().layers[0].vertices.len(), 6);
+
+        let mut strip = Mesh::new(PrimitiveTopology::TriangleStrip, RenderAssetUsages::all());
+        strip.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            [3, 0, 2, 1, 5, 4]
+                .iter()
+                .map(|i| [corners[*i][0], corners[*i][1], 0.0])
+                .collect::<Vec<[f32; 3]>>(),
+        );
+        let from_strip = NavMesh::try_from_bevy_mesh(&strip).unwrap();
+        assert_eq!(from_strip.get().layers[0].polygons.len(), 4);
+
+        // Triangles from the strip alternate their winding, they all end up turning the same way
+        for navmesh in [from_list, from_strip] {
+            let layer = &navmesh.get().layers[0];
+            let windings = layer
+                .polygons
+                .iter()
+                .map(|polygon| {
+                    let [a, b, c] =
+                        [0, 1, 2].map(|i| layer.vertices[polygon.vertices[i] as usize].coords);
+                    (b - a).perp_dot(c - a).signum()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(windings, vec![windings[0]; 4]);
+        }
+    }
+
+    #[test]
+    fn invalid_meshes_are_errors() {
+        let lines = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::all());
+        assert!(matches!(
+            NavMesh::try_from_bevy_mesh(&lines),
+            Err(NavMeshFromMeshError::UnsupportedTopology(
+                PrimitiveTopology::LineList
+            ))
+        ));
+        assert!(NavMesh::from_bevy_mesh(&lines).is_none());
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all());
+        assert!(matches!(
+            NavMesh::try_from_bevy_mesh(&mesh),
+            Err(NavMeshFromMeshError::MissingPositions)
+        ));
+
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]],
+        );
+        mesh.insert_indices(Indices::U32(vec![0, 1, 3]));
+        assert!(matches!(
+            NavMesh::try_from_bevy_mesh(&mesh),
+            Err(NavMeshFromMeshError::IndexOutOfBounds(3))
+        ));
+    }
+
     fn assert_same_navmesh(expected: NavMesh, actual: NavMesh) {
         let expected_mesh = expected.mesh;
         let actual_mesh = actual.mesh;
@@ -0,0 +1,532 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    sync::{Arc, RwLock},
+};
+
+use bevy::{
+    math::{Vec2, Vec3},
+    prelude::Component,
+};
+use polyanya::Path;
+
+use crate::{
+    NavMesh,
+    areas::{AreaFilter, NavMeshArea},
+    path_cache::copy_path,
+    topology::EPSILON,
+};
+
+/// A connection between two points that are not connected through the polygons of a [`NavMesh`], like a
+/// jump, a ladder or a teleporter.
+///
+/// The points are in the local space of the entity. The [`NavmeshUpdaterPlugin`](crate::prelude::NavmeshUpdaterPlugin)
+/// adds the link to the [`NavMesh`]es it updates, filtered like obstacles by
+/// [`NavMeshSettings::filter_obstacles`](crate::prelude::NavMeshSettings::filter_obstacles). When a [`NavMesh`] is
+/// built from several layers, each link is added by the layers that don't filter it out, and its ends can be on
+/// any layer.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct OffMeshLink {
+    /// Where the link starts.
+    pub start: Vec3,
+    /// Where the link ends.
+    pub end: Vec3,
+    /// Cost of going through the link, compared to the distance traveled on the [`NavMesh`].
+    pub cost: f32,
+    /// If the link can also be used from its end to its start.
+    pub bidirectional: bool,
+    /// Flags of the link, checked like the flags of a [`NavMeshArea`] by an [`AreaFilter`] or closed gates.
+    ///
+    /// The default value is [`NavMeshArea::DEFAULT_FLAGS`].
+    pub flags: u32,
+}
+
+impl Default for OffMeshLink {
+    fn default() -> Self {
+        Self {
+            start: Vec3::ZERO,
+            end: Vec3::ZERO,
+            cost: 0.0,
+            bidirectional: true,
+            flags: NavMeshArea::DEFAULT_FLAGS,
+        }
+    }
+}
+
+/// An off mesh link of a [`NavMesh`], in mesh space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NavMeshLink {
+    /// Where the link starts.
+    pub start: Vec2,
+    /// Where the link ends.
+    pub end: Vec2,
+    /// Cost of going through the link, compared to the distance traveled on the [`NavMesh`].
+    pub cost: f32,
+    /// If the link can also be used from its end to its start.
+    pub bidirectional: bool,
+    /// Flags of the link, checked like the flags of a [`NavMeshArea`] by an [`AreaFilter`] or closed gates.
+    ///
+    /// The default value is [`NavMeshArea::DEFAULT_FLAGS`].
+    pub flags: u32,
+}
+
+struct Step {
+    cost: f32,
+    node: usize,
+}
+
+impl PartialEq for Step {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Step {}
+
+impl PartialOrd for Step {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Step {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed to make the `BinaryHeap` a min-heap
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// How a node of the search was reached.
+enum Reached {
+    Walking(Path),
+    Link,
+}
+
+/// Number of filters for which the walking paths between links are kept.
+const MAX_LINK_GRAPHS: usize = 16;
+
+/// Walking paths between the ends of the off mesh links, for one filter.
+struct LinkGraph {
+    /// If each end of each link is in the mesh, at index `2 * link` for its start and `2 * link + 1` for its end.
+    on_mesh: Vec<bool>,
+    /// Walking path and cost from each end to each other end where a link can be entered.
+    walks: Vec<Vec<Option<(Path, f32)>>>,
+}
+
+/// Walking paths between the ends of the off mesh links of a [`NavMesh`], computed the first time they
+/// are needed for a filter, then kept until the links, the areas or the closed gates change.
+///
+/// At most [`MAX_LINK_GRAPHS`] filters are kept, all of them are dropped when there are too many.
+#[derive(Default)]
+pub(crate) struct LinkGraphs(RwLock<HashMap<AreaFilter, Arc<LinkGraph>>>);
+
+impl std::fmt::Debug for LinkGraphs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LinkGraphs").finish_non_exhaustive()
+    }
+}
+
+impl Clone for LinkGraphs {
+    fn clone(&self) -> Self {
+        LinkGraphs::default()
+    }
+}
+
+impl NavMesh {
+    /// Off mesh links of this [`NavMesh`], in mesh space.
+    pub fn links(&self) -> &[NavMeshLink] {
+        &self.links
+    }
+
+    /// Sets the off mesh links of this [`NavMesh`], in mesh space. This clears the path cache.
+    pub fn set_links(&mut self, links: Vec<NavMeshLink>) {
+        self.links_by_layer = vec![];
+        self.links = links;
+        self.link_graphs = Default::default();
+        self.clear_path_cache();
+    }
+
+    /// Sets the off mesh links added for each layer by the updater.
+    pub(crate) fn set_links_by_layer(&mut self, links_by_layer: Vec<Vec<NavMeshLink>>) {
+        self.links = links_by_layer.concat();
+        self.links_by_layer = links_by_layer;
+        self.link_graphs = Default::default();
+        self.clear_path_cache();
+    }
+
+    /// Position of a node of the search through links: the start, the goal, then the start and end of each link.
+    fn link_node(&self, node: usize, from: Vec2, to: Vec2) -> Vec2 {
+        match node {
+            0 => from,
+            1 => to,
+            n if n % 2 == 0 => self.links[n / 2 - 1].start,
+            n => self.links[n / 2 - 1].end,
+        }
+    }
+
+    /// The other end of a link, if it can be entered from this end of it.
+    fn through_link(&self, node: usize, filter: &AreaFilter) -> Option<usize> {
+        match node {
+            0 | 1 => None,
+            n if !filter.allows(self.links[n / 2 - 1].flags) => None,
+            n if n % 2 == 0 => Some(n + 1),
+            n if self.links[n / 2 - 1].bidirectional => Some(n - 1),
+            _ => None,
+        }
+    }
+
+    fn link_graph(&self, filter: &AreaFilter) -> Arc<LinkGraph> {
+        if let Some(graph) = self.link_graphs.0.read().unwrap().get(filter) {
+            return graph.clone();
+        }
+        let ends = self.links.len() * 2;
+        let position = |end: usize| self.link_node(end + 2, Vec2::ZERO, Vec2::ZERO);
+        let on_mesh = (0..ends)
+            .map(|end| self.locate(position(end)).is_some())
+            .collect::<Vec<_>>();
+        let walks = (0..ends)
+            .map(|from| {
+                (0..ends)
+                    .map(|to| {
+                        (from != to
+                            && on_mesh[from]
+                            && on_mesh[to]
+                            && self.through_link(to + 2, filter).is_some())
+                        .then(|| self.walking_path(position(from), position(to), filter))
+                        .flatten()
+                    })
+                    .collect()
+            })
+            .collect();
+        let graph = Arc::new(LinkGraph { on_mesh, walks });
+        let mut graphs = self.link_graphs.0.write().unwrap();
+        if graphs.len() >= MAX_LINK_GRAPHS {
+            graphs.clear();
+        }
+        graphs.insert(*filter, graph.clone());
+        graph
+    }
+
+    /// Path between two points without using links, and its cost.
+    fn walking_path(&self, from: Vec2, to: Vec2, filter: &AreaFilter) -> Option<(Path, f32)> {
+        if from.distance(to) < EPSILON {
+            return Some((
+                Path {
+                    length: 0.0,
+                    path: vec![],
+                    #[cfg(feature = "detailed-layers")]
+                    path_with_layers: vec![],
+                },
+                0.0,
+            ));
+        }
+        if !self.may_be_connected(from, to) {
+            return None;
+        }
+        if *filter == AreaFilter::default() && !self.has_costs() {
+            self.mesh.path(from, to).map(|path| {
+                let length = path.length;
+                (path, length)
+            })
+        } else {
+            self.weighted_path(from, to, filter)
+        }
+    }
+
+    /// Finds the cheapest path between two points, walking on the mesh and going through links.
+    ///
+    /// The `length` of the path is the distance walked plus the cost of the links used. The direct walking
+    /// path is searched first, then links that can't be cheaper than it are skipped. Walking paths
+    /// between the ends of links are searched once per filter, only the paths from `from` and to `to`
+    /// are searched for each query.
+    pub(crate) fn path_with_links(
+        &self,
+        from: Vec2,
+        to: Vec2,
+        filter: &AreaFilter,
+    ) -> Option<Path> {
+        let graph = self.link_graph(filter);
+        let position = |node: usize| self.link_node(node, from, to);
+        let link_cost = |node: usize| self.links[node / 2 - 1].cost.max(0.0);
+        let count = self.links.len() * 2 + 2;
+        // Links whose ends are not on the mesh can't be used
+        let usable = |node: usize| node == 1 || graph.on_mesh[node - 2];
+
+        // Walking costs at least the distance times the cost of the cheapest area
+        let walk_bound =
+            |a: usize, b: usize| position(a).distance(position(b)) * self.cheapest_cost;
+        // Lower bound of the cost from a node to the goal, when entering the link at that node
+        let remaining = |node: usize| match node {
+            1 => 0.0,
+            n => self
+                .through_link(n, filter)
+                .map_or(f32::INFINITY, |exit| link_cost(n) + walk_bound(exit, 1)),
+        };
+
+        let mut best = vec![f32::INFINITY; count];
+        let mut came_from: Vec<Option<(usize, Reached)>> = (0..count).map(|_| None).collect();
+        let mut visited = vec![false; count];
+        best[0] = 0.0;
+        let mut to_visit = BinaryHeap::from([Step { cost: 0.0, node: 0 }]);
+        if let Some((path, walk_cost)) = self.walking_path(from, to, filter) {
+            best[1] = walk_cost;
+            came_from[1] = Some((0, Reached::Walking(path)));
+            to_visit.push(Step {
+                cost: walk_cost,
+                node: 1,
+            });
+        }
+        while let Some(Step { cost, node }) = to_visit.pop() {
+            if visited[node] {
+                continue;
+            }
+            visited[node] = true;
+            if node == 1 {
+                break;
+            }
+            if let Some(exit) = self.through_link(node, filter)
+                && usable(exit)
+            {
+                let next_cost = cost + link_cost(node);
+                if next_cost < best[exit] {
+                    best[exit] = next_cost;
+                    came_from[exit] = Some((node, Reached::Link));
+                    to_visit.push(Step {
+                        cost: next_cost,
+                        node: exit,
+                    });
+                }
+            }
+            for next in (1..count)
+                .filter(|next| !visited[*next] && usable(*next) && !(node == 0 && *next == 1))
+            {
+                // Skip the links that can't be cheaper than the best path found so far
+                if cost + walk_bound(node, next) + remaining(next) >= best[1] {
+                    continue;
+                }
+                let walk = if node == 0 || next == 1 {
+                    self.walking_path(position(node), position(next), filter)
+                } else {
+                    graph.walks[node - 2][next - 2]
+                        .as_ref()
+                        .map(|(path, walk_cost)| (copy_path(path), *walk_cost))
+                };
+                let Some((path, walk_cost)) = walk else {
+                    continue;
+                };
+                let next_cost = cost + walk_cost;
+                if next_cost < best[next] {
+                    best[next] = next_cost;
+                    came_from[next] = Some((node, Reached::Walking(path)));
+                    to_visit.push(Step {
+                        cost: next_cost,
+                        node: next,
+                    });
+                }
+            }
+        }
+        if !visited[1] {
+            return None;
+        }
+
+        let mut steps = vec![];
+        let mut current = 1;
+        while let Some((previous, reached)) = came_from[current].take() {
+            steps.push((current, reached));
+            current = previous;
+        }
+        let mut path = Path {
+            length: 0.0,
+            path: vec![],
+            #[cfg(feature = "detailed-layers")]
+            path_with_layers: vec![],
+        };
+        for (node, reached) in steps.into_iter().rev() {
+            match reached {
+                Reached::Walking(step) => {
+                    path.length += step.length;
+                    path.path.extend(step.path);
+                    #[cfg(feature = "detailed-layers")]
+                    path.path_with_layers.extend(step.path_with_layers);
+                }
+                Reached::Link => {
+                    let exit = position(node);
+                    path.length += link_cost(node);
+                    path.path.push(exit);
+                    #[cfg(feature = "detailed-layers")]
+                    path.path_with_layers
+                        .push((exit, self.locate(exit).map_or(0, |polygon| polygon.layer)));
+                }
+            }
+        }
+        Some(path)
+    }
+
+    /// Islands that can be reached from `island`, walking and going through links allowed by `filter`.
+    pub(crate) fn reachable_islands(&self, island: u32, filter: &AreaFilter) -> Vec<u32> {
+        let islands = self.islands();
+        let island_of = |point: Vec2| {
+            self.locate(point)
+                .and_then(|polygon| islands.island(polygon))
+        };
+        let jumps = self
+            .links
+            .iter()
+            .filter(|link| filter.allows(link.flags))
+            .flat_map(|link| {
+                let (start, end) = (island_of(link.start), island_of(link.end));
+                [
+                    start.zip(end),
+                    end.zip(start).filter(|_| link.bidirectional),
+                ]
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        let mut reachable = vec![island];
+        let mut index = 0;
+        while let Some(current) = reachable.get(index).copied() {
+            for (_, to) in jumps.iter().filter(|(from, _)| *from == current) {
+                if !reachable.contains(to) {
+                    reachable.push(*to);
+                }
+            }
+            index += 1;
+        }
+        reachable
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use bevy::math::vec2;
+
+    use super::*;
+
+    pub(crate) fn two_islands() -> NavMesh {
+        // Two rooms separated by a wall, with no way between them
+        NavMesh::from_edge_and_obstacles(
+            vec![
+                vec2(0.0, 0.0),
+                vec2(20.0, 0.0),
+                vec2(20.0, 10.0),
+                vec2(0.0, 10.0),
+            ],
+            vec![vec![
+                vec2(9.0, -1.0),
+                vec2(11.0, -1.0),
+                vec2(11.0, 11.0),
+                vec2(9.0, 11.0),
+            ]],
+        )
+    }
+
+    #[test]
+    fn jump_over_wall() {
+        let mut navmesh = two_islands();
+        let (from, to) = (vec2(1.0, 5.0), vec2(19.0, 5.0));
+        assert!(navmesh.path(from, to).is_none());
+
+        navmesh.set_links(vec![NavMeshLink {
+            start: vec2(8.0, 2.0),
+            end: vec2(12.0, 2.0),
+            cost: 1.0,
+            bidirectional: false,
+            flags: NavMeshArea::DEFAULT_FLAGS,
+        }]);
+        let path = navmesh.path(from, to).unwrap();
+        assert_eq!(
+            path.path,
+            vec![vec2(8.0, 2.0), vec2(12.0, 2.0), vec2(19.0, 5.0)]
+        );
+        let walked = from.distance(vec2(8.0, 2.0)) + vec2(12.0, 2.0).distance(to);
+        assert!((path.length - walked - 1.0).abs() < 0.001);
+
+        // The link only goes one way
+        assert!(navmesh.path(to, from).is_none());
+    }
+
+    #[test]
+    fn cheapest_link() {
+        let mut navmesh = two_islands();
+        let link = |y: f32, cost: f32| NavMeshLink {
+            start: vec2(8.0, y),
+            end: vec2(12.0, y),
+            cost,
+            bidirectional: true,
+            flags: NavMeshArea::DEFAULT_FLAGS,
+        };
+        navmesh.set_links(vec![link(1.0, 1.0), link(9.0, 1.0), link(5.0, 100.0)]);
+        let path = navmesh.path(vec2(19.0, 8.0), vec2(1.0, 8.0)).unwrap();
+        assert_eq!(path.path[..2], [vec2(12.0, 9.0), vec2(8.0, 9.0)]);
+
+        // Links are also used with a filter
+        let path = navmesh
+            .path_filtered(
+                vec2(19.0, 8.0),
+                vec2(1.0, 8.0),
+                &AreaFilter {
+                    exclude: 2,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(path.path[..2], [vec2(12.0, 9.0), vec2(8.0, 9.0)]);
+    }
+
+    #[test]
+    fn direct_path_and_bounded_graphs() {
+        let mut navmesh = two_islands();
+        navmesh.set_links(vec![NavMeshLink {
+            start: vec2(8.0, 5.0),
+            end: vec2(12.0, 5.0),
+            cost: 0.0,
+            bidirectional: true,
+            flags: NavMeshArea::DEFAULT_FLAGS,
+        }]);
+        // A link that can't make the path shorter is not used
+        let path = navmesh.path(vec2(1.0, 1.0), vec2(1.0, 9.0)).unwrap();
+        assert_eq!(path.path, vec![vec2(1.0, 9.0)]);
+
+        let graphs = |navmesh: &NavMesh| navmesh.link_graphs.0.read().unwrap().len();
+        for exclude in 0..(MAX_LINK_GRAPHS as u32 * 2) {
+            let filter = AreaFilter {
+                exclude: exclude << 8,
+                ..Default::default()
+            };
+            assert!(
+                navmesh
+                    .path_filtered(vec2(1.0, 5.0), vec2(19.0, 5.0), &filter)
+                    .is_some()
+            );
+            assert!(graphs(&navmesh) <= MAX_LINK_GRAPHS);
+        }
+        navmesh.close_gates(1 << 30);
+        assert_eq!(graphs(&navmesh), 0);
+    }
+
+    #[test]
+    fn connected_through_links() {
+        let mut navmesh = two_islands();
+        let (from, to) = (vec2(1.0, 5.0), vec2(19.0, 5.0));
+        assert!(!navmesh.are_connected(from, to));
+        let partial = navmesh.path_to_closest(from, to).unwrap();
+        assert!(partial.is_partial);
+
+        navmesh.set_links(vec![NavMeshLink {
+            start: vec2(8.0, 2.0),
+            end: vec2(12.0, 2.0),
+            cost: 1.0,
+            bidirectional: false,
+            flags: NavMeshArea::DEFAULT_FLAGS,
+        }]);
+        assert!(navmesh.are_connected(from, to));
+        assert!(!navmesh.are_connected(to, from));
+        assert_eq!(navmesh.hierarchical_path(from, to), navmesh.path(from, to));
+        let partial = navmesh.path_to_closest(from, vec2(25.0, 5.0)).unwrap();
+        assert!(partial.is_partial);
+        assert!(partial.path.path.last().unwrap().distance(vec2(20.0, 5.0)) < 0.01);
+
+        navmesh.close_gates(NavMeshArea::DEFAULT_FLAGS);
+        assert!(!navmesh.are_connected(from, to));
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    color::{Color, ColorToComponents},
+    math::{Vec2, Vec3},
+    prelude::Mesh,
+    render::{mesh::Indices, render_asset::RenderAssetUsages, render_resource::PrimitiveTopology},
+};
+
+use polyanya::Layer;
+
+use crate::{NavMesh, PolygonRef, topology};
+
+/// Colors of the vertices of a [`Mesh`] created with [`NavMesh::to_mesh_with`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum MeshColors {
+    /// The [`Mesh`] doesn't have a [`Mesh::ATTRIBUTE_COLOR`].
+    #[default]
+    None,
+    /// Color of each layer, by index. Layers after the last color start again from the first one.
+    PerLayer(Vec<Color>),
+    /// Color of each polygon. Polygons without a color are white.
+    PerPolygon(HashMap<PolygonRef, Color>),
+}
+
+/// Settings to create a [`Mesh`] from a [`NavMesh`] with [`NavMesh::to_mesh_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshExportSettings {
+    /// Layers to include. All layers are included if `None`.
+    pub layers: Option<HashSet<u8>>,
+    /// Scale the vertices of each layer by its `scale`. Only used with the `detailed-layers` feature.
+    pub apply_layer_scale: bool,
+    /// Colors of the vertices.
+    pub colors: MeshColors,
+}
+
+impl Default for MeshExportSettings {
+    fn default() -> Self {
+        Self {
+            layers: None,
+            apply_layer_scale: true,
+            colors: MeshColors::None,
+        }
+    }
+}
+
+/// Coordinates of the vertices of a layer in mesh space: scaled by the `scale` of the layer with the
+/// `detailed-layers` feature if `scaled`, then moved to the `offset` of the layer.
+pub(crate) fn layer_coords(layer: &Layer, scaled: bool) -> Vec<Vec2> {
+    #[cfg(feature = "detailed-layers")]
+    let scale = if scaled { layer.scale } else { Vec2::ONE };
+    #[cfg(not(feature = "detailed-layers"))]
+    let scale = {
+        let _ = scaled;
+        Vec2::ONE
+    };
+    layer
+        .vertices
+        .iter()
+        .map(|vertex| vertex.coords * scale + layer.offset)
+        .collect()
+}
+
+impl NavMesh {
+    /// Creates a [`Mesh`] from this [`NavMesh`], suitable to render the walkable surface.
+    ///
+    /// Unlike [`NavMesh::to_mesh`], this covers all the layers selected in `settings` with their offsets, and
+    /// the [`Mesh`] has normals, UVs and optionally colors. Each polygon has its own vertices so that it can
+    /// have its own color. UVs go from `0.0` to `1.0` over the bounding box of the exported polygons.
+    pub fn to_mesh_with(&self, settings: &MeshExportSettings) -> Mesh {
+        let layers = self
+            .mesh
+            .layers
+            .iter()
+            .map(|layer| layer_coords(layer, settings.apply_layer_scale))
+            .collect::<Vec<_>>();
+        let polygons = topology::polygons(&self.mesh)
+            .filter(|polygon| {
+                settings
+                    .layers
+                    .as_ref()
+                    .is_none_or(|layers| layers.contains(&polygon.layer))
+            })
+            .map(|polygon| {
+                let layer = &layers[polygon.layer as usize];
+                let coords = self.mesh.layers[polygon.layer as usize].polygons
+                    [polygon.polygon as usize]
+                    .vertices
+                    .iter()
+                    .filter_map(|v| layer.get(*v as usize))
+                    .copied()
+                    .collect::<Vec<_>>();
+                (polygon, coords)
+            })
+            .filter(|(_, coords)| coords.len() >= 3)
+            .collect::<Vec<_>>();
+
+        let (min, max) = polygons
+            .iter()
+            .flat_map(|(_, coords)| coords.iter())
+            .fold((Vec2::MAX, Vec2::MIN), |(min, max), c| {
+                (min.min(*c), max.max(*c))
+            });
+        let size = (max - min).max(Vec2::splat(f32::EPSILON));
+
+        let mesh_to_world = self.transform();
+        let normal: [f32; 3] = (mesh_to_world.rotation * Vec3::Z).into();
+
+        let mut positions: Vec<[f32; 3]> = vec![];
+        let mut uvs: Vec<[f32; 2]> = vec![];
+        let mut colors: Vec<[f32; 4]> = vec![];
+        let mut indices: Vec<u32> = vec![];
+        for (polygon, coords) in &polygons {
+            let first = positions.len() as u32;
+            positions.extend(
+                coords
+                    .iter()
+                    .map(|c| mesh_to_world.transform_point(c.extend(0.0)).to_array()),
+            );
+            uvs.extend(coords.iter().map(|c| ((*c - min) / size).to_array()));
+            let color = match &settings.colors {
+                MeshColors::None => None,
+                MeshColors::PerLayer(colors) if colors.is_empty() => Some(Color::WHITE),
+                MeshColors::PerLayer(colors) => Some(colors[polygon.layer as usize % colors.len()]),
+                MeshColors::PerPolygon(colors) => {
+                    Some(colors.get(polygon).copied().unwrap_or(Color::WHITE))
+                }
+            };
+            if let Some(color) = color {
+                let color = color.to_linear().to_f32_array();
+                colors.extend(coords.iter().map(|_| color));
+            }
+            indices
+                .extend((2..coords.len() as u32).flat_map(|i| [first, first + i - 1, first + i]));
+        }
+
+        let mut new_mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all());
+        new_mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![normal; positions.len()]);
+        new_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        new_mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        if settings.colors != MeshColors::None {
+            new_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        }
+        new_mesh.insert_indices(Indices::U32(indices));
+        new_mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        math::vec2,
+        render::mesh::{MeshVertexAttribute, VertexAttributeValues},
+    };
+
+    use super::*;
+
+    fn values(mesh: &Mesh, attribute: MeshVertexAttribute) -> &VertexAttributeValues {
+        mesh.attribute(attribute).unwrap()
+    }
+
+    /// Two 10 by 10 squares stitched side by side, the second one in a layer offset by `(10.0, 0.0)`.
+    fn two_squares() -> polyanya::Mesh {
+        let square = |offset: Vec2| {
+            let mut layer = polyanya::Triangulation::from_outer_edges(&[
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(10.0, 10.0),
+                vec2(0.0, 10.0),
+            ])
+            .as_layer();
+            layer.offset = offset;
+            layer
+        };
+        let mut mesh = polyanya::Mesh {
+            layers: vec![square(Vec2::ZERO), square(vec2(10.0, 0.0))],
+            ..Default::default()
+        };
+        mesh.stitch_at_points(
+            vec![((0, 1), vec![vec2(10.0, 0.0), vec2(10.0, 10.0)])],
+            false,
+        );
+        mesh
+    }
+
+    fn positions(mesh: &Mesh) -> &Vec<[f32; 3]> {
+        let VertexAttributeValues::Float32x3(positions) = values(mesh, Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("positions should be 3D");
+        };
+        positions
+    }
+
+    #[test]
+    fn export_layers() {
+        let mesh = two_squares();
+        let navmesh = NavMesh::from_polyanya_mesh(mesh);
+        let per_layer = navmesh.get().layers[0]
+            .polygons
+            .iter()
+            .map(|p| p.vertices.len())
+            .sum::<usize>();
+
+        let exported = navmesh.to_mesh_with(&MeshExportSettings {
+            colors: MeshColors::PerLayer(vec![Color::BLACK, Color::WHITE]),
+            ..Default::default()
+        });
+        assert_eq!(exported.count_vertices(), per_layer * 2);
+        assert!(positions(&exported).iter().any(|p| p[0] == 20.0));
+        let VertexAttributeValues::Float32x3(normals) = values(&exported, Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("normals should be 3D");
+        };
+        assert!(normals.iter().all(|n| *n == [0.0, 0.0, 1.0]));
+        let VertexAttributeValues::Float32x2(uvs) = values(&exported, Mesh::ATTRIBUTE_UV_0) else {
+            panic!("UVs should be 2D");
+        };
+        assert!(uvs.contains(&[1.0, 1.0]));
+        assert!(uvs.iter().flatten().all(|uv| (0.0..=1.0).contains(uv)));
+        let VertexAttributeValues::Float32x4(colors) = values(&exported, Mesh::ATTRIBUTE_COLOR)
+        else {
+            panic!("colors should be 4D");
+        };
+        assert_eq!(colors[0], [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(colors[colors.len() - 1], [1.0, 1.0, 1.0, 1.0]);
+
+        let second_layer = navmesh.to_mesh_with(&MeshExportSettings {
+            layers: Some(HashSet::from([1])),
+            ..Default::default()
+        });
+        assert_eq!(second_layer.count_vertices(), per_layer);
+        assert!(second_layer.attribute(Mesh::ATTRIBUTE_COLOR).is_none());
+    }
+
+    #[test]
+    fn export_all_layers() {
+        let navmesh = NavMesh::from_polyanya_mesh(two_squares());
+        let vertices = navmesh
+            .get()
+            .layers
+            .iter()
+            .map(|layer| layer.vertices.len())
+            .sum::<usize>();
+        for exported in [navmesh.to_mesh(), navmesh.to_wireframe_mesh()] {
+            assert_eq!(exported.count_vertices(), vertices);
+            let positions = positions(&exported);
+            assert!(positions.iter().any(|p| p[0] == 20.0));
+            assert!(
+                exported
+                    .indices()
+                    .unwrap()
+                    .iter()
+                    .all(|i| i < positions.len())
+            );
+        }
+    }
+
+    #[cfg(feature = "detailed-layers")]
+    #[test]
+    fn scale_before_offset() {
+        let mut mesh = two_squares();
+        mesh.layers[1].scale = vec2(2.0, 1.0);
+        let navmesh = NavMesh::from_polyanya_mesh(mesh);
+        let scaled = navmesh.to_mesh_with(&MeshExportSettings {
+            layers: Some(HashSet::from([1])),
+            apply_layer_scale: true,
+            ..Default::default()
+        });
+        // The corner at `(10.0, 10.0)` of the second layer is scaled to `(20.0, 10.0)`, then offset
+        let corner = [30.0, 10.0, 0.0];
+        assert!(positions(&scaled).contains(&corner));
+        assert!(positions(&scaled).iter().all(|p| p[0] >= 10.0));
+        let unscaled = navmesh.to_mesh_with(&MeshExportSettings {
+            layers: Some(HashSet::from([1])),
+            apply_layer_scale: false,
+            ..Default::default()
+        });
+        assert!(positions(&unscaled).contains(&[20.0, 10.0, 0.0]));
+        assert!(positions(&navmesh.to_mesh()).contains(&corner));
+        assert!(positions(&navmesh.to_wireframe_mesh()).contains(&corner));
+    }
+}
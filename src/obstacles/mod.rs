@@ -13,6 +13,10 @@ pub(crate) mod parry2d;
 pub(crate) mod primitive;
 
 /// Trait to mark a component as the source of position and shape of an obstacle.
+///
+/// Obstacles are holes in the [`NavMesh`](crate::NavMesh): there is no polygon left to carry flags, so they
+/// block every path. To block only some agents, add a [`NavMeshArea`](crate::prelude::NavMeshArea) with a flag
+/// excluded by their [`AreaFilter`](crate::AreaFilter) on the entity instead.
 pub trait ObstacleSource: Component + Clone {
     /// Get the polygon of the obstacle in the local space of the mesh.
     fn get_polygons(
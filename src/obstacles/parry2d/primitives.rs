@@ -103,7 +103,7 @@ impl Shape for EllipseShape {
         ShapeType::Custom
     }
 
-    fn as_typed_shape(&self) -> TypedShape {
+    fn as_typed_shape(&self) -> TypedShape<'_> {
         TypedShape::Custom(self)
     }
 
@@ -307,7 +307,7 @@ impl Shape for RegularPolygonShape {
         ShapeType::Custom
     }
 
-    fn as_typed_shape(&self) -> TypedShape {
+    fn as_typed_shape(&self) -> TypedShape<'_> {
         TypedShape::Custom(self)
     }
 
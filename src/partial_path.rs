@@ -0,0 +1,131 @@
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+use polyanya::Path;
+
+use crate::{AreaFilter, NavMesh, TransformedPath};
+
+/// A path that may stop before its destination, if the destination can't be reached.
+#[derive(Debug, PartialEq)]
+pub struct PartialPath<P = Path> {
+    /// The path, to the destination or to the reachable point closest to it.
+    pub path: P,
+    /// `true` if the destination couldn't be reached, and the path stops at the reachable point closest to it.
+    pub is_partial: bool,
+}
+
+impl NavMesh {
+    /// Finds the shortest path between two points, or to the point closest to `to` if it can't be reached.
+    ///
+    /// This happens when `to` is outside of the mesh, or in an island that can't be reached from `from`, walking or
+    /// through off mesh links, or behind closed gates.
+    /// Returns `None` only if `from` is not in the mesh.
+    pub fn path_to_closest(&self, from: Vec2, to: Vec2) -> Option<PartialPath> {
+        if let Some(path) = self.path(from, to) {
+            return Some(PartialPath {
+                path,
+                is_partial: false,
+            });
+        }
+        let start = self.closest_point(from, self.search_delta())?;
+        let goal = if self.closed_gates == 0 {
+            let islands = self.islands();
+            self.closest_point_in(
+                to,
+                f32::INFINITY,
+                self.reachable_islands(islands.island(start.polygon)?, &AreaFilter::default())
+                    .into_iter()
+                    .flat_map(|island| islands.polygons(island)),
+            )?
+        } else {
+            // Closed gates can split an island
+            self.closest_point_in(
+                to,
+                f32::INFINITY,
+                self.reachable_polygons(start.polygon).into_iter(),
+            )?
+        };
+        let path = if goal.point.distance(start.point) < self.search_delta() {
+            Path {
+                length: 0.0,
+                path: vec![goal.point],
+                #[cfg(feature = "detailed-layers")]
+                path_with_layers: vec![(goal.point, goal.polygon.layer)],
+            }
+        } else {
+            self.path(from, goal.point)?
+        };
+        Some(PartialPath {
+            path,
+            is_partial: true,
+        })
+    }
+
+    /// Finds the shortest path between two points, or to the point closest to `to` if it can't be reached.
+    ///
+    /// Inputs and results are transformed using the [`NavMesh::transform`].
+    pub fn transformed_path_to_closest(
+        &self,
+        from: Vec3,
+        to: Vec3,
+    ) -> Option<PartialPath<TransformedPath>> {
+        let inner_from = self.world_to_mesh().transform_point(from).xy();
+        let inner_to = self.world_to_mesh().transform_point(to).xy();
+        self.path_to_closest(inner_from, inner_to)
+            .map(|partial| PartialPath {
+                path: self.transform_path(partial.path),
+                is_partial: partial.is_partial,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use crate::{links::tests::two_islands, tests::square_with_hole};
+
+    #[test]
+    fn as_close_as_possible() {
+        let navmesh = square_with_hole();
+
+        let reachable = navmesh
+            .path_to_closest(vec2(1.0, 1.0), vec2(9.0, 9.0))
+            .unwrap();
+        assert!(!reachable.is_partial);
+        assert_eq!(reachable.path.path.last(), Some(&vec2(9.0, 9.0)));
+
+        let in_hole = navmesh
+            .path_to_closest(vec2(1.0, 5.0), vec2(4.5, 5.0))
+            .unwrap();
+        assert!(in_hole.is_partial);
+        assert!(in_hole.path.path.last().unwrap().distance(vec2(4.0, 5.0)) < 0.01);
+
+        let outside = navmesh
+            .path_to_closest(vec2(1.0, 1.0), vec2(15.0, 5.0))
+            .unwrap();
+        assert!(outside.is_partial);
+        assert!(outside.path.path.last().unwrap().distance(vec2(10.0, 5.0)) < 0.01);
+
+        assert!(
+            navmesh
+                .path_to_closest(vec2(5.0, 5.0), vec2(1.0, 1.0))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn closest_on_start_island() {
+        let navmesh = two_islands();
+
+        // The target is in the other room, the path stops against the wall in the room of the start
+        let other_island = navmesh
+            .path_to_closest(vec2(1.0, 2.0), vec2(15.0, 8.0))
+            .unwrap();
+        assert!(other_island.is_partial);
+        let end = *other_island.path.path.last().unwrap();
+        assert!(end.distance(vec2(9.0, 8.0)) < 0.01);
+        assert!(navmesh.are_connected(vec2(1.0, 2.0), end));
+    }
+}
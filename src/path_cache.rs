@@ -0,0 +1,257 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::RwLock,
+};
+
+use bevy::math::Vec2;
+use polyanya::Path;
+
+use crate::{NavMesh, topology::PolygonRef};
+
+/// Settings of the path cache of a [`NavMesh`].
+///
+/// Paths between points in the same polygons share the same cached result. When the start or destination
+/// are not exactly the ones of the cached path, they are adjusted to the ones requested, and the cached
+/// result is only used if they are in line of sight of the next and previous steps of the path. Paths
+/// from or to points outside of the mesh are not cached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathCacheSettings {
+    /// Maximum number of paths kept. The oldest ones are removed first.
+    pub capacity: usize,
+}
+
+impl Default for PathCacheSettings {
+    fn default() -> Self {
+        Self { capacity: 1024 }
+    }
+}
+
+/// Polygons of the start and destination of a path.
+pub(crate) type Key = (PolygonRef, PolygonRef);
+
+struct Entry {
+    from: Vec2,
+    to: Vec2,
+    path: Option<Path>,
+}
+
+#[derive(Default)]
+struct Entries {
+    paths: HashMap<Key, Entry>,
+    order: VecDeque<Key>,
+}
+
+/// Cached paths of a [`NavMesh`]. A new [`NavMesh`] starts with an empty cache.
+pub(crate) struct PathCache {
+    settings: PathCacheSettings,
+    entries: RwLock<Entries>,
+}
+
+impl std::fmt::Debug for PathCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PathCache")
+            .field("settings", &self.settings)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Clone for PathCache {
+    fn clone(&self) -> Self {
+        PathCache::new(self.settings)
+    }
+}
+
+pub(crate) fn copy_path(path: &Path) -> Path {
+    Path {
+        length: path.length,
+        path: path.path.clone(),
+        #[cfg(feature = "detailed-layers")]
+        path_with_layers: path.path_with_layers.clone(),
+    }
+}
+
+impl PathCache {
+    pub(crate) fn new(settings: PathCacheSettings) -> Self {
+        Self {
+            settings,
+            entries: RwLock::new(Entries::default()),
+        }
+    }
+
+    /// Returns the cached result, with its start and destination moved to `from` and `to`, and whether
+    /// it was cached for exactly those points.
+    fn get(&self, key: &Key, from: Vec2, to: Vec2) -> Option<(Option<Path>, bool)> {
+        let entries = self.entries.read().unwrap();
+        let cached = entries.paths.get(key)?;
+        if cached.from == from && cached.to == to {
+            return Some((cached.path.as_ref().map(copy_path), true));
+        }
+        let path = cached.path.as_ref().map(|path| {
+            let mut path = copy_path(path);
+            if let Some(first) = path.path.first() {
+                path.length += from.distance(*first) - cached.from.distance(*first);
+            }
+            let start = path.path.iter().rev().nth(1).copied().unwrap_or(from);
+            if let Some(last) = path.path.last_mut() {
+                path.length += start.distance(to) - start.distance(*last);
+                *last = to;
+            }
+            #[cfg(feature = "detailed-layers")]
+            if let Some((last, _)) = path.path_with_layers.last_mut() {
+                *last = to;
+            }
+            path
+        });
+        Some((path, false))
+    }
+
+    fn insert(&self, key: Key, from: Vec2, to: Vec2, path: Option<&Path>) {
+        if self.settings.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.write().unwrap();
+        let entry = Entry {
+            from,
+            to,
+            path: path.map(copy_path),
+        };
+        if entries.paths.insert(key, entry).is_none() {
+            entries.order.push_back(key);
+        }
+        while entries.order.len() > self.settings.capacity {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.paths.remove(&oldest);
+            }
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        *self.entries.write().unwrap() = Entries::default();
+    }
+}
+
+impl NavMesh {
+    /// Enables caching the results of [`NavMesh::path`] and the queries using it, or disables it with `None`.
+    ///
+    /// The cache is kept until the [`NavMesh`] is replaced, for example when it is rebuilt by the [`NavmeshUpdaterPlugin`](crate::prelude::NavmeshUpdaterPlugin).
+    pub fn set_path_cache(&mut self, settings: Option<PathCacheSettings>) {
+        self.path_cache = settings.map(PathCache::new);
+    }
+
+    /// Settings of the path cache, if enabled.
+    pub fn path_cache(&self) -> Option<PathCacheSettings> {
+        self.path_cache.as_ref().map(|cache| cache.settings)
+    }
+
+    /// Removes all the paths in the cache.
+    pub fn clear_path_cache(&self) {
+        if let Some(cache) = &self.path_cache {
+            cache.clear();
+        }
+    }
+
+    /// Key of a path query in the path cache: the polygons of `from` and `to`.
+    ///
+    /// `None` if the path cache is disabled or one of the points is not in the mesh. Points are only
+    /// located when the cache is enabled, and once per query.
+    pub(crate) fn path_cache_key(&self, from: Vec2, to: Vec2) -> Option<Key> {
+        self.path_cache.as_ref()?;
+        self.locate(from).zip(self.locate(to))
+    }
+
+    /// Cached result of a path query, if the path cache has one that still works from `from` to `to`.
+    pub(crate) fn cached_path(
+        &self,
+        key: Option<Key>,
+        from: Vec2,
+        to: Vec2,
+    ) -> Option<Option<Path>> {
+        let cache = self.path_cache.as_ref()?;
+        let (cached, exact) = cache.get(&key?, from, to)?;
+        if !exact && let Some(path) = &cached {
+            let first = path.path.first().copied().unwrap_or(to);
+            let before_last = path.path.iter().rev().nth(1).copied().unwrap_or(from);
+            if !self.raycast(from, first).is_clear() || !self.raycast(before_last, to).is_clear() {
+                return None;
+            }
+        }
+        Some(cached)
+    }
+
+    /// Adds the result of a path query to the path cache, if it has a key.
+    pub(crate) fn cache_path(&self, key: Option<Key>, from: Vec2, to: Vec2, path: Option<&Path>) {
+        if let (Some(cache), Some(key)) = (&self.path_cache, key) {
+            cache.insert(key, from, to, path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use super::*;
+    use crate::tests::square_with_hole;
+
+    #[test]
+    fn cached_paths() {
+        let mut navmesh = square_with_hole();
+        navmesh.set_path_cache(Some(PathCacheSettings { capacity: 1 }));
+        let cached = |navmesh: &NavMesh, from, to| {
+            navmesh.cached_path(navmesh.path_cache_key(from, to), from, to)
+        };
+
+        let path = navmesh.path(vec2(1.0, 5.0), vec2(9.0, 5.0)).unwrap();
+        assert_eq!(
+            cached(&navmesh, vec2(1.0, 5.0), vec2(9.0, 5.0)),
+            Some(Some(copy_path(&path)))
+        );
+
+        // In the same polygons, the same path is reused with the start and destination adjusted
+        let close = navmesh.path(vec2(1.02, 5.0), vec2(9.01, 5.0)).unwrap();
+        assert_eq!(close.path.last(), Some(&vec2(9.01, 5.0)));
+        assert_eq!(
+            close.path[..close.path.len() - 1],
+            path.path[..path.path.len() - 1]
+        );
+        let shortest = navmesh
+            .uncached_path(vec2(1.02, 5.0), vec2(9.01, 5.0), &Default::default())
+            .unwrap();
+        assert!((close.length - shortest.length).abs() < 0.001);
+
+        // The oldest path is removed
+        assert!(navmesh.path(vec2(5.0, 1.0), vec2(5.0, 9.0)).is_some());
+        assert!(cached(&navmesh, vec2(5.0, 1.0), vec2(5.0, 9.0)).is_some());
+        assert!(cached(&navmesh, vec2(1.0, 5.0), vec2(9.0, 5.0)).is_none());
+
+        // Paths from outside of the mesh are not cached
+        assert!(navmesh.path(vec2(5.0, 5.0), vec2(9.0, 5.0)).is_none());
+        assert!(
+            navmesh
+                .path_cache_key(vec2(5.0, 5.0), vec2(9.0, 5.0))
+                .is_none()
+        );
+        assert!(cached(&navmesh, vec2(5.0, 1.0), vec2(5.0, 9.0)).is_some());
+
+        navmesh.clear_path_cache();
+        assert!(cached(&navmesh, vec2(5.0, 1.0), vec2(5.0, 9.0)).is_none());
+    }
+
+    #[test]
+    fn cached_unreachable_paths() {
+        let mut navmesh = crate::links::tests::two_islands();
+        navmesh.set_path_cache(Some(PathCacheSettings::default()));
+        let (from, to) = (vec2(1.0, 5.0), vec2(19.0, 5.0));
+        assert!(navmesh.path(from, to).is_none());
+        assert_eq!(
+            navmesh.cached_path(navmesh.path_cache_key(from, to), from, to),
+            Some(None)
+        );
+        // Any point of the same polygons gets the same result
+        let (from, to) = (vec2(1.0, 5.1), vec2(19.0, 5.1));
+        assert_eq!(
+            navmesh.cached_path(navmesh.path_cache_key(from, to), from, to),
+            Some(None)
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+use polyanya::Path;
+
+use crate::{AreaFilter, NavMesh, TransformedPath};
+
+impl NavMesh {
+    /// Finds the shortest path from a point to the closest of several goals.
+    ///
+    /// Goals are compared by the `length` of the path to reach them, which includes the cost of the off mesh links
+    /// used, not by their straight line distance. Returns the index of the goal reached in `goals` with the path, or
+    /// `None` if no goal can be reached.
+    ///
+    /// All goals are first searched at once on the graph of polygons to find a goal that is close by path. Then only
+    /// the goals that could still have a shorter path are checked with [`NavMesh::path`], so the path returned is
+    /// the shortest one to any goal.
+    pub fn path_to_any(&self, from: Vec2, goals: &[Vec2]) -> Option<(usize, Path)> {
+        let mut best = self
+            .closest_goal(from, goals)
+            .and_then(|index| self.path(from, goals[index]).map(|path| (index, path)));
+
+        let mut candidates = goals
+            .iter()
+            .enumerate()
+            .map(|(index, goal)| (index, *goal, self.shortest_length(from, *goal)))
+            .collect::<Vec<_>>();
+        candidates.sort_by(|(_, _, d1), (_, _, d2)| d1.total_cmp(d2));
+        for (index, goal, shortest) in candidates {
+            if let Some((reached, path)) = &best {
+                if path.length <= shortest {
+                    break;
+                }
+                if *reached == index {
+                    continue;
+                }
+            }
+            if let Some(path) = self.path(from, goal)
+                && best
+                    .as_ref()
+                    .is_none_or(|(_, best)| path.length < best.length)
+            {
+                best = Some((index, path));
+            }
+        }
+        best
+    }
+
+    /// Index of a goal close by path, found with a single search on the graph of polygons.
+    ///
+    /// Off mesh links can't be followed by this search, so this is `None` if the mesh has some.
+    fn closest_goal(&self, from: Vec2, goals: &[Vec2]) -> Option<usize> {
+        if !self.links.is_empty() {
+            return None;
+        }
+        let start = self.closest_point(from, self.snap_distance())?;
+        let (indices, located): (Vec<_>, Vec<_>) = goals
+            .iter()
+            .enumerate()
+            .filter_map(|(index, goal)| {
+                self.closest_point(*goal, self.snap_distance())
+                    .map(|closest| (index, (closest.polygon, closest.point)))
+            })
+            .unzip();
+        let filter = AreaFilter {
+            exclude: self.closed_gates,
+            ..Default::default()
+        };
+        let (reached, _) = self.cheapest_route((start.polygon, start.point), &located, &filter)?;
+        Some(indices[reached])
+    }
+
+    /// A length that no path between two points can be shorter than.
+    ///
+    /// This is the straight line distance, or less when an off mesh link can bring closer to `to`.
+    fn shortest_length(&self, from: Vec2, to: Vec2) -> f32 {
+        self.links
+            .iter()
+            .flat_map(|link| {
+                let cost = link.cost.max(0.0);
+                [
+                    Some(cost + link.end.distance(to)),
+                    link.bidirectional.then(|| cost + link.start.distance(to)),
+                ]
+            })
+            .flatten()
+            .fold(from.distance(to), f32::min)
+    }
+
+    /// Finds the shortest path from a point to the closest of several goals.
+    ///
+    /// Inputs and results are transformed using the [`NavMesh::transform`].
+    pub fn transformed_path_to_any(
+        &self,
+        from: Vec3,
+        goals: &[Vec3],
+    ) -> Option<(usize, TransformedPath)> {
+        let world_to_mesh = self.world_to_mesh();
+        let inner_from = world_to_mesh.transform_point(from).xy();
+        let inner_goals = goals
+            .iter()
+            .map(|goal| world_to_mesh.transform_point(*goal).xy())
+            .collect::<Vec<_>>();
+        self.path_to_any(inner_from, &inner_goals)
+            .map(|(index, path)| (index, self.transform_path(path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use super::*;
+    use crate::{NavMeshLink, areas::NavMeshArea};
+
+    #[test]
+    fn closest_by_path() {
+        // A wall between the start and the goal closest in a straight line
+        let navmesh = NavMesh::from_edge_and_obstacles(
+            vec![
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(10.0, 10.0),
+                vec2(0.0, 10.0),
+            ],
+            vec![vec![
+                vec2(4.5, 0.0),
+                vec2(5.5, 0.0),
+                vec2(5.5, 8.0),
+                vec2(4.5, 8.0),
+            ]],
+        );
+        let goals = [vec2(7.0, 1.0), vec2(1.0, 6.0), vec2(20.0, 20.0)];
+
+        let (index, path) = navmesh.path_to_any(vec2(3.0, 1.0), &goals).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(path.path.last(), Some(&goals[1]));
+
+        let (index, _) = navmesh.path_to_any(vec2(7.0, 7.0), &goals).unwrap();
+        assert_eq!(index, 0);
+
+        assert!(navmesh.path_to_any(vec2(3.0, 1.0), &goals[2..]).is_none());
+        assert!(navmesh.path_to_any(vec2(3.0, 1.0), &[]).is_none());
+
+        // Same length as the shortest of the paths to each goal
+        let goals = (0..10)
+            .flat_map(|x| (0..10).map(move |y| vec2(x as f32 + 0.5, y as f32 + 0.5)))
+            .filter(|goal| navmesh.is_in_mesh(*goal) && goal.x > 5.5)
+            .collect::<Vec<_>>();
+        for from in [vec2(1.0, 1.0), vec2(3.0, 9.0), vec2(9.5, 0.5)] {
+            let (index, path) = navmesh.path_to_any(from, &goals).unwrap();
+            let shortest = goals
+                .iter()
+                .filter_map(|goal| navmesh.path(from, *goal))
+                .map(|path| path.length)
+                .fold(f32::INFINITY, f32::min);
+            assert_eq!(path.length, shortest);
+            assert_eq!(path.path.last(), Some(&goals[index]));
+        }
+    }
+
+    #[test]
+    fn closest_through_link() {
+        // A long corridor, with a free link from near the start to near its end
+        let mut navmesh = NavMesh::from_edge_and_obstacles(
+            vec![
+                vec2(-1.0, -1.0),
+                vec2(110.0, -1.0),
+                vec2(110.0, 1.0),
+                vec2(-1.0, 1.0),
+            ],
+            vec![],
+        );
+        navmesh.set_links(vec![NavMeshLink {
+            start: vec2(1.0, 0.0),
+            end: vec2(100.0, 0.0),
+            cost: 0.0,
+            bidirectional: false,
+            flags: NavMeshArea::DEFAULT_FLAGS,
+        }]);
+        let goals = [vec2(50.0, 0.0), vec2(101.0, 0.0)];
+
+        let (index, path) = navmesh.path_to_any(vec2(0.0, 0.0), &goals).unwrap();
+        assert_eq!(index, 1);
+        assert!((path.length - 2.0).abs() < 0.001);
+    }
+}
@@ -0,0 +1,178 @@
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+
+use crate::{
+    NavMesh,
+    topology::{self, PolygonRef},
+};
+
+/// A view on a polygon of a [`NavMesh`].
+///
+/// Methods without the `transformed_` prefix work in mesh space, the others are transformed using [`NavMesh::transform`].
+#[derive(Debug, Clone, Copy)]
+pub struct NavMeshPolygon<'a> {
+    navmesh: &'a NavMesh,
+    polygon: PolygonRef,
+}
+
+impl<'a> NavMeshPolygon<'a> {
+    /// Reference to this polygon, that can be stored and used with [`NavMesh::polygon`].
+    pub fn id(&self) -> PolygonRef {
+        self.polygon
+    }
+
+    /// Layer of this polygon.
+    pub fn layer(&self) -> u8 {
+        self.polygon.layer
+    }
+
+    /// Vertices of this polygon, in counter clockwise order.
+    pub fn vertices(&self) -> Vec<Vec2> {
+        topology::polygon_coords(&self.navmesh.mesh, self.polygon)
+    }
+
+    /// Vertices of this polygon, in counter clockwise order.
+    pub fn transformed_vertices(&self) -> Vec<Vec3> {
+        let transform = self.navmesh.transform();
+        self.vertices()
+            .into_iter()
+            .map(|vertex| transform.transform_point(vertex.extend(0.0)))
+            .collect()
+    }
+
+    /// Centroid of this polygon.
+    pub fn centroid(&self) -> Vec2 {
+        topology::centroid(&self.vertices())
+    }
+
+    /// Centroid of this polygon.
+    pub fn transformed_centroid(&self) -> Vec3 {
+        self.navmesh
+            .transform()
+            .transform_point(self.centroid().extend(0.0))
+    }
+
+    /// Area of this polygon.
+    pub fn area(&self) -> f32 {
+        topology::area(&self.vertices())
+    }
+
+    /// Area of this polygon, taking the scale of the [`NavMesh::transform`] into account.
+    pub fn transformed_area(&self) -> f32 {
+        let vertices = self.transformed_vertices();
+        vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .map(|(a, b)| a.cross(*b))
+            .sum::<Vec3>()
+            .length()
+            / 2.0
+    }
+
+    /// Checks if a point is in this polygon.
+    pub fn contains(&self, point: Vec2) -> bool {
+        topology::contains(&self.vertices(), point)
+    }
+
+    /// Polygons sharing an edge with this one, including polygons from stitched layers.
+    ///
+    /// The `n`-th item is the polygon on the other side of the edge starting at the `n`-th vertex,
+    /// or `None` if that edge is on the border of the mesh.
+    pub fn neighbours(&self) -> Vec<Option<NavMeshPolygon<'a>>> {
+        (0..self.vertices().len())
+            .map(|edge| {
+                topology::neighbour(&self.navmesh.mesh, self.polygon, edge).map(|polygon| {
+                    NavMeshPolygon {
+                        navmesh: self.navmesh,
+                        polygon,
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+impl NavMesh {
+    /// Finds the polygon containing a point.
+    ///
+    /// If layers overlap, the polygon of the first layer is returned.
+    pub fn polygon_at(&self, point: Vec2) -> Option<PolygonRef> {
+        self.locate(point)
+    }
+
+    /// Finds the polygon containing a point.
+    ///
+    /// Inputs are transformed using the [`NavMesh::transform`].
+    pub fn transformed_polygon_at(&self, point: Vec3) -> Option<PolygonRef> {
+        self.polygon_at(self.world_to_mesh().transform_point(point).xy())
+    }
+
+    /// Gets a view on a polygon, or `None` if it doesn't exist in this mesh.
+    pub fn polygon(&self, polygon: PolygonRef) -> Option<NavMeshPolygon<'_>> {
+        self.mesh
+            .layers
+            .get(polygon.layer as usize)
+            .and_then(|layer| layer.polygons.get(polygon.polygon as usize))
+            .map(|_| NavMeshPolygon {
+                navmesh: self,
+                polygon,
+            })
+    }
+
+    /// Iterates over all the polygons of the mesh, in all layers.
+    pub fn polygons(&self) -> impl Iterator<Item = NavMeshPolygon<'_>> {
+        topology::polygons(&self.mesh).map(|polygon| NavMeshPolygon {
+            navmesh: self,
+            polygon,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        math::{Quat, vec2, vec3},
+        transform::components::Transform,
+    };
+
+    use super::*;
+    use crate::tests::square_with_hole;
+
+    #[test]
+    fn polygon_topology() {
+        let mut navmesh = square_with_hole();
+        navmesh.set_transform(
+            Transform::from_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2))
+                .with_scale(Vec3::splat(2.0)),
+        );
+
+        let total_area = navmesh.polygons().map(|p| p.area()).sum::<f32>();
+        assert!((total_area - 96.0).abs() < 0.001);
+        let total_area = navmesh
+            .polygons()
+            .map(|p| p.transformed_area())
+            .sum::<f32>();
+        assert!((total_area - 96.0 * 4.0).abs() < 0.01);
+
+        assert!(navmesh.polygon_at(vec2(5.0, 5.0)).is_none());
+        let polygon = navmesh.polygon_at(vec2(1.0, 1.0)).unwrap();
+        assert_eq!(
+            navmesh.transformed_polygon_at(vec3(2.0, 0.0, 2.0)),
+            Some(polygon)
+        );
+        let polygon = navmesh.polygon(polygon).unwrap();
+        assert!(polygon.contains(polygon.centroid()));
+        for neighbour in polygon.neighbours().into_iter().flatten() {
+            assert!(
+                neighbour
+                    .neighbours()
+                    .iter()
+                    .flatten()
+                    .any(|p| p.id() == polygon.id())
+            );
+        }
+        assert!(polygon.neighbours().iter().any(Option::is_some));
+    }
+}
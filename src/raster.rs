@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+
+use bevy::{
+    color::{ColorToComponents, Luminance},
+    image::Image,
+    math::{IVec2, Vec2},
+};
+use polyanya::{Layer, Triangulation};
+
+use crate::{NavMesh, topology};
+
+/// Settings to build a [`NavMesh`] from an [`Image`] with [`NavMesh::from_image`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NavMeshImageSettings {
+    /// Pixels with a luminance, multiplied by their alpha, above this value are walkable.
+    pub threshold: f32,
+    /// Pixels below the `threshold` are walkable instead of the ones above.
+    pub invert: bool,
+    /// Size of a pixel in the [`NavMesh`].
+    pub pixel_size: f32,
+    /// Maximum distance between the traced contours and the edges of the [`NavMesh`], in pixels.
+    ///
+    /// Use `0.0` to keep every point of the contours.
+    pub simplification: f32,
+}
+
+impl Default for NavMeshImageSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            invert: false,
+            pixel_size: 1.0,
+            simplification: 0.5,
+        }
+    }
+}
+
+impl NavMesh {
+    /// Builds a [`NavMesh`] from the walkable pixels of an [`Image`], typically a black and white mask.
+    ///
+    /// The contours of the walkable areas are traced with marching squares, then simplified. The
+    /// bottom left corner of the [`Image`] is at the origin of the mesh, and the mesh goes up to
+    /// `(width * pixel_size, height * pixel_size)`. Walkable areas completely surrounded by blocked
+    /// pixels are kept, but are not connected to each other.
+    ///
+    /// Returns `None` if the pixels of the [`Image`] can't be read, or if there is no walkable area.
+    pub fn from_image(image: &Image, settings: &NavMeshImageSettings) -> Option<NavMesh> {
+        let (width, height) = (image.width(), image.height());
+        let mut walkable = Vec::with_capacity((width * height) as usize);
+        // Rows of images go down, rows of the mesh go up
+        for y in (0..height).rev() {
+            for x in 0..width {
+                let color = image.get_color_at(x, y).ok()?;
+                let value = color.luminance() * color.to_linear().to_f32_array()[3];
+                walkable.push((value >= settings.threshold) != settings.invert);
+            }
+        }
+        Self::from_walkable_grid(width as usize, height as usize, &walkable, settings)
+    }
+
+    fn from_walkable_grid(
+        width: usize,
+        height: usize,
+        walkable: &[bool],
+        settings: &NavMeshImageSettings,
+    ) -> Option<NavMesh> {
+        let contours = trace_contours(width, height, walkable)
+            .into_iter()
+            .map(|contour| simplify(&contour, settings.simplification))
+            .filter(|contour| contour.len() >= 3)
+            .map(|contour| {
+                contour
+                    .into_iter()
+                    .map(|point| point * settings.pixel_size)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // Walkable areas go clockwise, and areas blocked inside them counter clockwise
+        let (outer_edges, obstacles): (Vec<_>, Vec<_>) = contours
+            .into_iter()
+            .map(|contour| (topology::area(&contour), contour))
+            .partition(|(area, _)| *area < 0.0);
+        let mut regions = outer_edges
+            .into_iter()
+            .map(|(area, mut edges)| {
+                edges.reverse();
+                (-area, edges, vec![])
+            })
+            .collect::<Vec<_>>();
+        // Smallest areas first so that each obstacle goes to the innermost area around it
+        regions.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+        for (_, obstacle) in obstacles {
+            if let Some((_, _, region_obstacles)) = regions
+                .iter_mut()
+                .find(|(_, edges, _)| topology::inside(edges, obstacle[0]))
+            {
+                region_obstacles.push(obstacle);
+            }
+        }
+
+        let mut layer = Layer::default();
+        for (_, edges, region_obstacles) in regions {
+            let mut triangulation = Triangulation::from_outer_edges(&edges);
+            triangulation.add_obstacles(region_obstacles);
+            let mut mesh = triangulation.as_navmesh();
+            for _i in 0..3 {
+                if mesh.merge_polygons() {
+                    break;
+                }
+            }
+            let Some(region) = mesh.layers.pop() else {
+                continue;
+            };
+            let vertex_offset = layer.vertices.len() as u32;
+            let polygon_offset = layer.polygons.len() as u32;
+            layer
+                .vertices
+                .extend(region.vertices.into_iter().map(|mut vertex| {
+                    vertex
+                        .polygons
+                        .iter_mut()
+                        .filter(|polygon| **polygon != u32::MAX)
+                        .for_each(|polygon| *polygon += polygon_offset);
+                    vertex
+                }));
+            layer
+                .polygons
+                .extend(region.polygons.into_iter().map(|mut polygon| {
+                    polygon
+                        .vertices
+                        .iter_mut()
+                        .for_each(|vertex| *vertex += vertex_offset);
+                    polygon
+                }));
+        }
+        if layer.polygons.is_empty() {
+            return None;
+        }
+        layer.bake();
+
+        let mut mesh = polyanya::Mesh {
+            layers: vec![layer],
+            ..Default::default()
+        };
+        mesh.set_search_delta(0.01);
+        Some(Self::from_polyanya_mesh(mesh))
+    }
+}
+
+/// Traces the contours between walkable and blocked cells, with blocked cells on their left.
+///
+/// Cells outside of the grid are blocked. Points are in cell units, with the cell `(0, 0)` going
+/// from `(0.0, 0.0)` to `(1.0, 1.0)`.
+fn trace_contours(width: usize, height: usize, walkable: &[bool]) -> Vec<Vec<Vec2>> {
+    let blocked = |x: i32, y: i32| {
+        x < 0
+            || y < 0
+            || x >= width as i32
+            || y >= height as i32
+            || !walkable[y as usize * width + x as usize]
+    };
+
+    // Points are stored with doubled coordinates to stay on integers. The center of the cell `(x, y)`
+    // is at `(2x + 1, 2y + 1)`, and the middle of its bottom edge at `(2x + 1, 2y)`.
+    let mut next = HashMap::<IVec2, IVec2>::new();
+    // Starts of the segments in the order they were found, so that contours are traced in the same order
+    // every time
+    let mut starts = vec![];
+    for y in -1..height as i32 {
+        for x in -1..width as i32 {
+            // Square between the centers of four cells, starting bottom left and going counter clockwise
+            let case = blocked(x, y) as u8
+                | (blocked(x + 1, y) as u8) << 1
+                | (blocked(x + 1, y + 1) as u8) << 2
+                | (blocked(x, y + 1) as u8) << 3;
+            let bottom = IVec2::new(2 * x + 2, 2 * y + 1);
+            let right = IVec2::new(2 * x + 3, 2 * y + 2);
+            let top = IVec2::new(2 * x + 2, 2 * y + 3);
+            let left = IVec2::new(2 * x + 1, 2 * y + 2);
+            let segments: &[(IVec2, IVec2)] = match case {
+                1 => &[(bottom, left)],
+                2 => &[(right, bottom)],
+                3 => &[(right, left)],
+                4 => &[(top, right)],
+                // Blocked cells touching by a corner are not connected
+                5 => &[(bottom, left), (top, right)],
+                6 => &[(top, bottom)],
+                7 => &[(top, left)],
+                8 => &[(left, top)],
+                9 => &[(bottom, top)],
+                10 => &[(right, bottom), (left, top)],
+                11 => &[(right, top)],
+                12 => &[(left, right)],
+                13 => &[(bottom, right)],
+                14 => &[(left, bottom)],
+                _ => &[],
+            };
+            for (from, to) in segments {
+                next.insert(*from, *to);
+                starts.push(*from);
+            }
+        }
+    }
+
+    let mut contours = vec![];
+    for start in starts {
+        if !next.contains_key(&start) {
+            continue;
+        }
+        let mut contour = vec![];
+        let mut current = start;
+        while let Some(to) = next.remove(&current) {
+            contour.push(current.as_vec2() / 2.0);
+            current = to;
+        }
+        contours.push(contour);
+    }
+    contours
+}
+
+/// Simplifies a closed contour with the Ramer-Douglas-Peucker algorithm.
+fn simplify(contour: &[Vec2], epsilon: f32) -> Vec<Vec2> {
+    fn simplify_open(points: &[Vec2], epsilon: f32, kept: &mut Vec<Vec2>) {
+        let (first, last) = (points[0], points[points.len() - 1]);
+        let farthest = points[1..points.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(i, point)| {
+                (
+                    i + 1,
+                    topology::closest_on_segment(*point, first, last).distance(*point),
+                )
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        match farthest {
+            Some((index, distance)) if distance > epsilon => {
+                simplify_open(&points[..=index], epsilon, kept);
+                simplify_open(&points[index..], epsilon, kept);
+            }
+            _ => kept.push(first),
+        }
+    }
+
+    if contour.len() < 3 {
+        return contour.to_vec();
+    }
+    // Split the contour at the point the farthest from its start
+    let split = contour
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| {
+            a.distance_squared(contour[0])
+                .total_cmp(&b.distance_squared(contour[0]))
+        })
+        .map(|(i, _)| i)
+        .unwrap_or_default();
+    let mut kept = vec![];
+    simplify_open(&contour[..=split], epsilon, &mut kept);
+    simplify_open(
+        &contour[split..]
+            .iter()
+            .chain(std::iter::once(&contour[0]))
+            .copied()
+            .collect::<Vec<_>>(),
+        epsilon,
+        &mut kept,
+    );
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::RenderAssetUsages,
+        math::vec2,
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::*;
+
+    #[test]
+    fn walkable_pixels() {
+        // A 20x10 image, walkable except for its border, a wall in the middle with a gap at the top,
+        // and a closed room in the bottom right corner
+        let (width, height) = (20, 10);
+        let mut data = vec![];
+        for y in 0..height {
+            for x in 0..width {
+                let wall = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+                let middle = x == 10 && y >= 3;
+                let room = (x == 14 || y == 5) && x >= 14 && y >= 5;
+                let value = if wall || middle || room { 0 } else { 255 };
+                data.extend([value, value, value, 255]);
+            }
+        }
+        let image = Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::all(),
+        );
+
+        let navmesh = NavMesh::from_image(&image, &NavMeshImageSettings::default()).unwrap();
+        // Rows of the image go down, so the gap in the wall is at the top of the mesh
+        assert!(navmesh.is_in_mesh(vec2(5.5, 5.5)));
+        assert!(!navmesh.is_in_mesh(vec2(10.5, 5.5)));
+        assert!(!navmesh.is_in_mesh(vec2(0.2, 5.5)));
+        let path = navmesh.path(vec2(5.5, 5.5), vec2(12.5, 5.5)).unwrap();
+        assert!(path.path.iter().any(|p| p.y >= 7.0));
+        assert!(path.length > 7.0);
+
+        // The room is walkable, but not connected to the rest
+        assert!(navmesh.is_in_mesh(vec2(17.0, 2.5)));
+        assert!(navmesh.path(vec2(5.5, 5.5), vec2(17.0, 2.5)).is_none());
+
+        let inverted = NavMesh::from_image(
+            &image,
+            &NavMeshImageSettings {
+                invert: true,
+                pixel_size: 2.0,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(!inverted.is_in_mesh(vec2(11.0, 11.0)));
+        assert!(inverted.is_in_mesh(vec2(21.0, 11.0)));
+    }
+}
@@ -0,0 +1,292 @@
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+use polyanya::Mesh;
+
+use crate::{
+    NavMesh,
+    topology::{self, EPSILON, PolygonRef},
+};
+
+/// Result of a line of sight query on a [`NavMesh`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RaycastResult<Hit = RaycastHit> {
+    /// The segment is entirely in the navigable part of the mesh.
+    Clear,
+    /// The segment leaves the navigable part of the mesh.
+    Hit(Hit),
+    /// The starting point is not in the navigable part of the mesh.
+    OutOfMesh,
+}
+
+impl<Hit> RaycastResult<Hit> {
+    /// Returns `true` if the segment is entirely in the navigable part of the mesh.
+    pub fn is_clear(&self) -> bool {
+        matches!(self, RaycastResult::Clear)
+    }
+
+    /// Returns the hit, if the segment leaves the navigable part of the mesh.
+    pub fn hit(self) -> Option<Hit> {
+        match self {
+            RaycastResult::Hit(hit) => Some(hit),
+            _ => None,
+        }
+    }
+}
+
+/// Where a segment first leaves the navigable part of a [`NavMesh`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaycastHit {
+    /// Point where the segment leaves the mesh.
+    pub point: Vec2,
+    /// Border edge that was hit.
+    pub edge: [Vec2; 2],
+    /// Layer of the polygon the segment leaves from.
+    pub layer: u8,
+    /// Fraction of the segment that is navigable, between `0.0` and `1.0`.
+    pub fraction: f32,
+}
+
+/// Where a segment first leaves the navigable part of a [`NavMesh`], transformed using [`NavMesh::transform`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformedRaycastHit {
+    /// Point where the segment leaves the mesh.
+    pub point: Vec3,
+    /// Border edge that was hit.
+    pub edge: [Vec3; 2],
+    /// Layer of the polygon the segment leaves from.
+    pub layer: u8,
+    /// Fraction of the segment that is navigable, between `0.0` and `1.0`.
+    pub fraction: f32,
+}
+
+/// Polygons and edges crossed while walking a segment through the mesh.
+pub(crate) struct Walk {
+    /// Polygons visited, in order.
+    pub(crate) polygons: Vec<PolygonRef>,
+    /// Edges crossed between two consecutive polygons.
+    pub(crate) portals: Vec<[Vec2; 2]>,
+    /// `None` if the end of the segment was reached, otherwise the hit.
+    pub(crate) hit: Option<RaycastHit>,
+}
+
+/// Walks the straight segment from `from` to `to`, polygon by polygon, starting from `start`.
+pub(crate) fn walk(mesh: &Mesh, start: PolygonRef, from: Vec2, to: Vec2) -> Walk {
+    let mut walk = Walk {
+        polygons: vec![start],
+        portals: vec![],
+        hit: None,
+    };
+    let direction = to - from;
+    let probe = EPSILON / direction.length().max(EPSILON);
+    let max_steps = mesh.layers.iter().map(|l| l.polygons.len()).sum::<usize>() * 2 + 2;
+
+    let mut current = start;
+    let mut t_in = 0.0;
+    for _ in 0..max_steps {
+        let coords = topology::polygon_coords(mesh, current);
+        if topology::contains(&coords, to) {
+            return walk;
+        }
+
+        // The line leaves a convex polygon through the first edge it crosses outward
+        let exits = coords
+            .iter()
+            .zip(coords.iter().cycle().skip(1))
+            .enumerate()
+            .filter_map(|(i, (a, b))| {
+                let edge = *b - *a;
+                let denominator = edge.perp_dot(direction);
+                (denominator < 0.0).then(|| (edge.perp_dot(*a - from) / denominator, i))
+            })
+            .collect::<Vec<_>>();
+        let Some(first) = exits.iter().map(|(t, _)| *t).min_by(f32::total_cmp) else {
+            break;
+        };
+        // Collinear edges are crossed at the same time, keep the one actually containing the exit point
+        let first_exit = from + direction * first.max(t_in);
+        let Some((t, edge)) = exits
+            .into_iter()
+            .filter(|(t, _)| *t <= first + probe)
+            .min_by(|(_, e1), (_, e2)| {
+                let distance = |e: usize| {
+                    topology::closest_on_segment(
+                        first_exit,
+                        coords[e],
+                        coords[(e + 1) % coords.len()],
+                    )
+                    .distance(first_exit)
+                };
+                distance(*e1).total_cmp(&distance(*e2))
+            })
+        else {
+            break;
+        };
+        let t = t.clamp(t_in, 1.0);
+        let exit = from + direction * t;
+        let [a, b] = [coords[edge], coords[(edge + 1) % coords.len()]];
+
+        let probe_point = from + direction * (t + probe).min(1.0);
+        let layer = &mesh.layers[current.layer as usize];
+        let vertices = &layer.polygons[current.polygon as usize].vertices;
+        let through_vertex = if exit.distance(a) < EPSILON {
+            Some(vertices[edge])
+        } else if exit.distance(b) < EPSILON {
+            Some(vertices[(edge + 1) % vertices.len()])
+        } else {
+            None
+        };
+
+        let next = match through_vertex {
+            Some(vertex) => topology::vertex_polygons(mesh, current.layer, vertex)
+                .filter(|p| *p != current && !walk.polygons.contains(p))
+                .find(|p| topology::contains(&topology::polygon_coords(mesh, *p), probe_point)),
+            None => topology::neighbour(mesh, current, edge),
+        };
+
+        let Some(next) = next else {
+            walk.hit = Some(RaycastHit {
+                point: exit,
+                edge: [a, b],
+                layer: current.layer,
+                fraction: t,
+            });
+            return walk;
+        };
+        walk.portals.push(match through_vertex {
+            Some(_) => [exit, exit],
+            None => [a, b],
+        });
+        walk.polygons.push(next);
+        current = next;
+        t_in = t;
+    }
+
+    // Could not make progress, consider the segment blocked where it was
+    let exit = from + direction * t_in;
+    walk.hit = Some(RaycastHit {
+        point: exit,
+        edge: [exit, exit],
+        layer: current.layer,
+        fraction: t_in,
+    });
+    walk
+}
+
+impl NavMesh {
+    /// Checks if the straight segment between two points stays in the navigable part of the mesh.
+    ///
+    /// If it doesn't, the result contains where the segment first leaves the mesh and the border edge it hit.
+    /// Stitched layers are followed, the same way as [`NavMesh::path`] does.
+    pub fn raycast(&self, from: Vec2, to: Vec2) -> RaycastResult {
+        let Some(start) = self.locate(from) else {
+            return RaycastResult::OutOfMesh;
+        };
+        match walk(&self.mesh, start, from, to).hit {
+            None => RaycastResult::Clear,
+            Some(hit) => RaycastResult::Hit(hit),
+        }
+    }
+
+    /// Checks if the straight segment between two points stays in the navigable part of the mesh.
+    ///
+    /// Inputs and results are transformed using the [`NavMesh::transform`].
+    pub fn transformed_raycast(
+        &self,
+        from: Vec3,
+        to: Vec3,
+    ) -> RaycastResult<TransformedRaycastHit> {
+        let world_to_mesh = self.world_to_mesh();
+        let inner_from = world_to_mesh.transform_point(from).xy();
+        let inner_to = world_to_mesh.transform_point(to).xy();
+        let transform = self.transform();
+        match self.raycast(inner_from, inner_to) {
+            RaycastResult::Clear => RaycastResult::Clear,
+            RaycastResult::OutOfMesh => RaycastResult::OutOfMesh,
+            RaycastResult::Hit(hit) => RaycastResult::Hit(TransformedRaycastHit {
+                point: transform.transform_point(hit.point.extend(0.0)),
+                edge: hit.edge.map(|v| transform.transform_point(v.extend(0.0))),
+                layer: hit.layer,
+                fraction: hit.fraction,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+    use polyanya::{Layer, Polygon, Vertex};
+
+    use super::*;
+    use crate::tests::square_with_hole;
+
+    #[test]
+    fn clear_line_of_sight() {
+        let navmesh = square_with_hole();
+        assert!(navmesh.raycast(vec2(1.0, 1.0), vec2(9.0, 2.0)).is_clear());
+        assert!(navmesh.raycast(vec2(1.0, 1.0), vec2(1.0, 9.0)).is_clear());
+        assert!(navmesh.raycast(vec2(2.0, 2.0), vec2(2.0, 2.0)).is_clear());
+    }
+
+    #[test]
+    fn blocked_line_of_sight() {
+        let navmesh = square_with_hole();
+        let hit = navmesh
+            .raycast(vec2(1.0, 5.0), vec2(9.0, 5.0))
+            .hit()
+            .unwrap();
+        assert!(hit.point.distance(vec2(4.0, 5.0)) < 0.001);
+        assert!((hit.edge[0].x - 4.0).abs() < 0.001 && (hit.edge[1].x - 4.0).abs() < 0.001);
+        assert!((hit.fraction - 0.375).abs() < 0.001);
+
+        let hit = navmesh
+            .raycast(vec2(5.0, 9.0), vec2(5.0, 12.0))
+            .hit()
+            .unwrap();
+        assert!(hit.point.distance(vec2(5.0, 10.0)) < 0.001);
+
+        assert_eq!(
+            navmesh.raycast(vec2(5.0, 5.0), vec2(1.0, 1.0)),
+            RaycastResult::OutOfMesh
+        );
+    }
+
+    #[test]
+    fn through_vertices() {
+        let navmesh = square_with_hole();
+        // Diagonal passing exactly through the corners of the hole
+        assert!(!navmesh.raycast(vec2(1.0, 1.0), vec2(9.0, 9.0)).is_clear());
+        // Passing exactly along the side of the hole
+        assert!(navmesh.raycast(vec2(2.0, 4.0), vec2(8.0, 4.0)).is_clear());
+        // Ending exactly on a corner of the hole
+        assert!(navmesh.raycast(vec2(2.0, 2.0), vec2(4.0, 4.0)).is_clear());
+    }
+
+    #[test]
+    fn collinear_exit_edges() {
+        // The right side of the first polygon is split in two collinear edges, only the top one
+        // leads to another polygon
+        let mut layer = Layer::default();
+        layer.vertices = vec![
+            Vertex::new(vec2(0.0, 0.0), vec![0, u32::MAX]),
+            Vertex::new(vec2(2.0, 0.0), vec![0, u32::MAX]),
+            Vertex::new(vec2(2.0, 1.0), vec![1, 0, u32::MAX]),
+            Vertex::new(vec2(2.0, 2.0), vec![u32::MAX, 0, 1]),
+            Vertex::new(vec2(0.0, 2.0), vec![u32::MAX, 0]),
+            Vertex::new(vec2(4.0, 1.0), vec![1, u32::MAX]),
+            Vertex::new(vec2(4.0, 2.0), vec![u32::MAX, 1]),
+        ];
+        layer.polygons = vec![
+            Polygon::new(vec![0, 1, 2, 3, 4], false),
+            Polygon::new(vec![2, 5, 6, 3], false),
+        ];
+        let navmesh = NavMesh::from_polyanya_mesh(Mesh {
+            layers: vec![layer],
+            ..Default::default()
+        });
+        assert!(navmesh.raycast(vec2(1.0, 1.5), vec2(3.0, 1.5)).is_clear());
+        assert!(!navmesh.raycast(vec2(1.0, 0.5), vec2(3.0, 0.5)).is_clear());
+    }
+}
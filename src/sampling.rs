@@ -0,0 +1,311 @@
+use std::collections::HashSet;
+
+use bevy::{
+    math::{Vec2, Vec3, Vec3Swizzles},
+    prelude::TransformPoint,
+};
+use rand::Rng;
+
+use crate::{
+    NavMesh,
+    topology::{self, PolygonRef},
+};
+
+/// Polygons of a mesh with their cumulative area, to pick one with a probability proportional to its area.
+#[derive(Debug, Clone)]
+pub(crate) struct AreaTable {
+    polygons: Vec<PolygonRef>,
+    cumulative: Vec<f32>,
+}
+
+impl AreaTable {
+    fn new(mesh: &polyanya::Mesh) -> Self {
+        let mut total = 0.0;
+        let (polygons, cumulative) = topology::polygons(mesh)
+            .filter_map(|polygon| {
+                let area = topology::area(&topology::polygon_coords(mesh, polygon));
+                (area > 0.0).then(|| {
+                    total += area;
+                    (polygon, total)
+                })
+            })
+            .unzip();
+        AreaTable {
+            polygons,
+            cumulative,
+        }
+    }
+
+    fn pick(&self, rng: &mut impl Rng) -> Option<PolygonRef> {
+        let total = *self.cumulative.last()?;
+        let pick = rng.random_range(0.0..total);
+        let index = self
+            .cumulative
+            .partition_point(|area| *area <= pick)
+            .min(self.polygons.len() - 1);
+        Some(self.polygons[index])
+    }
+}
+
+/// Picks an index with a probability proportional to its weight.
+fn pick_weighted(rng: &mut impl Rng, weights: &[f32]) -> Option<usize> {
+    let total = weights.iter().sum::<f32>();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut pick = rng.random_range(0.0..total);
+    for (i, weight) in weights.iter().enumerate() {
+        if pick < *weight {
+            return Some(i);
+        }
+        pick -= weight;
+    }
+    weights.iter().rposition(|weight| *weight > 0.0)
+}
+
+/// Picks a point uniformly in a convex polygon.
+fn sample_polygon(rng: &mut impl Rng, coords: &[Vec2]) -> Option<Vec2> {
+    let triangles = (1..coords.len().saturating_sub(1))
+        .map(|i| [coords[0], coords[i], coords[i + 1]])
+        .collect::<Vec<_>>();
+    let weights = triangles
+        .iter()
+        .map(|[a, b, c]| (*b - *a).perp_dot(*c - *a).max(0.0))
+        .collect::<Vec<_>>();
+    let [a, b, c] = triangles[pick_weighted(rng, &weights)?];
+    let (mut u, mut v) = (rng.random::<f32>(), rng.random::<f32>());
+    if u + v > 1.0 {
+        (u, v) = (1.0 - u, 1.0 - v);
+    }
+    Some(a + (b - a) * u + (c - a) * v)
+}
+
+/// Clips a convex polygon to an axis aligned box.
+fn clip_to_box(coords: &[Vec2], min: Vec2, max: Vec2) -> Vec<Vec2> {
+    let mut clipped = coords.to_vec();
+    for (axis, bound, keep_below) in [
+        (0, min.x, false),
+        (0, max.x, true),
+        (1, min.y, false),
+        (1, max.y, true),
+    ] {
+        let inside = |p: Vec2| {
+            if keep_below {
+                p[axis] <= bound
+            } else {
+                p[axis] >= bound
+            }
+        };
+        let input = std::mem::take(&mut clipped);
+        for (a, b) in input.iter().zip(input.iter().cycle().skip(1)) {
+            if inside(*a) {
+                clipped.push(*a);
+            }
+            if inside(*a) != inside(*b) {
+                let t = (bound - a[axis]) / (b[axis] - a[axis]);
+                clipped.push(a.lerp(*b, t));
+            }
+        }
+    }
+    clipped
+}
+
+impl NavMesh {
+    /// Picks a random point in the navigable part of the mesh.
+    ///
+    /// Points are uniformly distributed over the whole mesh: larger polygons are picked more often.
+    /// The area of each polygon is computed the first time this is needed, then it's kept with the mesh.
+    pub fn random_point(&self, rng: &mut impl Rng) -> Option<Vec2> {
+        let picked = self
+            .area_table
+            .get_or_init(|| AreaTable::new(&self.mesh))
+            .pick(rng)?;
+        sample_polygon(rng, &topology::polygon_coords(&self.mesh, picked))
+    }
+
+    /// Picks a random point in the navigable part of the mesh.
+    ///
+    /// The result is transformed using the [`NavMesh::transform`].
+    pub fn transformed_random_point(&self, rng: &mut impl Rng) -> Option<Vec3> {
+        self.random_point(rng)
+            .map(|point| self.transform().transform_point(point.extend(0.0)))
+    }
+
+    /// Picks a random point in the navigable part of the mesh, in a circle around `center`.
+    ///
+    /// Only polygons that can be reached from `center` without leaving the bounding box of the circle are
+    /// considered, so that a path always exists between `center` and the point. Points are uniformly distributed
+    /// over the navigable part of the circle reached this way.
+    ///
+    /// Returns `None` if `center` is not in the mesh, or if no point was found in the circle after 100 tries.
+    pub fn random_point_in_circle(
+        &self,
+        center: Vec2,
+        radius: f32,
+        rng: &mut impl Rng,
+    ) -> Option<Vec2> {
+        let start = self.locate(center)?;
+        let (min, max) = (center - radius, center + radius);
+
+        // Polygons are visited from the one containing `center`, stopping at the ones outside of the
+        // bounding box of the circle so that the cost depends on the size of the circle, not of the mesh
+        let mut polygons = vec![];
+        let mut reached = HashSet::from([start]);
+        let mut to_visit = vec![start];
+        while let Some(polygon) = to_visit.pop() {
+            let coords = topology::polygon_coords(&self.mesh, polygon);
+            let clipped = clip_to_box(&coords, min, max);
+            if clipped.len() < 3 {
+                continue;
+            }
+            polygons.push(clipped);
+            for next in
+                (0..coords.len()).filter_map(|edge| topology::neighbour(&self.mesh, polygon, edge))
+            {
+                if reached.insert(next) {
+                    to_visit.push(next);
+                }
+            }
+        }
+        let weights = polygons
+            .iter()
+            .map(|coords| topology::area(coords).max(0.0))
+            .collect::<Vec<_>>();
+
+        // Polygons are clipped to the bounding box of the circle, most samples will be in the circle
+        for _ in 0..100 {
+            let picked = pick_weighted(rng, &weights)?;
+            let point = sample_polygon(rng, &polygons[picked])?;
+            if point.distance_squared(center) <= radius * radius {
+                return Some(point);
+            }
+        }
+        None
+    }
+
+    /// Picks a random point in the navigable part of the mesh, in a circle around `center`.
+    ///
+    /// Inputs and results are transformed using the [`NavMesh::transform`].
+    pub fn transformed_random_point_in_circle(
+        &self,
+        center: Vec3,
+        radius: f32,
+        rng: &mut impl Rng,
+    ) -> Option<Vec3> {
+        let inner_center = self.world_to_mesh().transform_point(center).xy();
+        self.random_point_in_circle(inner_center, radius, rng)
+            .map(|point| self.transform().transform_point(point.extend(0.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    fn two_rooms() -> NavMesh {
+        // Two rooms separated by a wall, connected on the bottom
+        NavMesh::from_edge_and_obstacles(
+            vec![
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(10.0, 10.0),
+                vec2(0.0, 10.0),
+            ],
+            vec![vec![
+                vec2(4.5, 2.0),
+                vec2(5.5, 2.0),
+                vec2(5.5, 10.0),
+                vec2(4.5, 10.0),
+            ]],
+        )
+    }
+
+    #[test]
+    fn random_points_are_in_mesh() {
+        let navmesh = two_rooms();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut left = 0;
+        for _ in 0..1000 {
+            let point = navmesh.random_point(&mut rng).unwrap();
+            assert!(navmesh.is_in_mesh(point));
+            if point.x < 4.5 {
+                left += 1;
+            }
+        }
+        // Almost half of the surface is on the left of the wall
+        assert!((440..540).contains(&left));
+    }
+
+    #[test]
+    fn random_points_in_circle_stay_on_island() {
+        // A closed room in the middle of the mesh, surrounded by walls
+        let navmesh = NavMesh::from_edge_and_obstacles(
+            vec![
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(10.0, 10.0),
+                vec2(0.0, 10.0),
+            ],
+            vec![
+                vec![
+                    vec2(6.0, 3.0),
+                    vec2(9.0, 3.0),
+                    vec2(9.0, 4.0),
+                    vec2(6.0, 4.0),
+                ],
+                vec![
+                    vec2(6.0, 6.0),
+                    vec2(9.0, 6.0),
+                    vec2(9.0, 7.0),
+                    vec2(6.0, 7.0),
+                ],
+                vec![
+                    vec2(6.0, 4.0),
+                    vec2(7.0, 4.0),
+                    vec2(7.0, 6.0),
+                    vec2(6.0, 6.0),
+                ],
+                vec![
+                    vec2(8.0, 4.0),
+                    vec2(9.0, 4.0),
+                    vec2(9.0, 6.0),
+                    vec2(8.0, 6.0),
+                ],
+            ],
+        );
+        assert_eq!(navmesh.islands().count(), 2);
+        let room = |point: Vec2| point.x > 7.0 && point.x < 8.0 && point.y > 4.0 && point.y < 6.0;
+        assert!(navmesh.is_in_mesh(vec2(7.5, 5.0)));
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let point = navmesh
+                .random_point_in_circle(vec2(5.0, 5.0), 4.0, &mut rng)
+                .unwrap();
+            assert!(navmesh.is_in_mesh(point));
+            assert!(point.distance(vec2(5.0, 5.0)) <= 4.0);
+            assert!(!room(point));
+        }
+    }
+
+    #[test]
+    fn random_points_in_circle() {
+        let navmesh = two_rooms();
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let point = navmesh
+                .random_point_in_circle(vec2(3.0, 5.0), 2.0, &mut rng)
+                .unwrap();
+            assert!(navmesh.is_in_mesh(point));
+            assert!(point.distance(vec2(3.0, 5.0)) <= 2.0);
+        }
+        assert!(
+            navmesh
+                .random_point_in_circle(vec2(5.0, 5.0), 2.0, &mut rng)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,474 @@
+use std::{error::Error, fmt::Display};
+
+use bevy::{
+    math::{Quat, Vec2, Vec3},
+    prelude::Transform,
+};
+use polyanya::{Layer, MeshError, Polygon, Vertex};
+use serde::{Deserialize, Serialize};
+
+use crate::{NavMesh, PathCacheSettings, areas::NavMeshArea, links::NavMeshLink};
+
+/// Error that can happen while reading a [`NavMesh`] serialized with [`NavMesh::to_ron`] or [`NavMesh::to_binary`].
+#[derive(Debug)]
+pub enum NavMeshDeserializeError {
+    /// Error parsing the RON format
+    Ron(ron::error::SpannedError),
+    /// Error decoding the binary format
+    Binary(bincode::error::DecodeError),
+    /// The data was read, but doesn't describe a valid mesh
+    MeshError(MeshError),
+}
+
+impl Display for NavMeshDeserializeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NavMeshDeserializeError::Ron(ron_error) => write!(f, "RON error: {ron_error}"),
+            NavMeshDeserializeError::Binary(decode_error) => {
+                write!(f, "Binary error: {decode_error}")
+            }
+            NavMeshDeserializeError::MeshError(mesh_error) => write!(f, "Mesh error: {mesh_error}"),
+        }
+    }
+}
+
+impl Error for NavMeshDeserializeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NavMeshDeserializeError::Ron(ron_error) => Some(ron_error),
+            NavMeshDeserializeError::Binary(decode_error) => Some(decode_error),
+            NavMeshDeserializeError::MeshError(mesh_error) => Some(mesh_error),
+        }
+    }
+}
+
+/// All versions of the format. New versions are added as new variants so that older files can still be read.
+#[derive(Serialize, Deserialize)]
+enum VersionedNavMesh {
+    V1(NavMeshV1),
+}
+
+#[derive(Serialize, Deserialize)]
+struct NavMeshV1 {
+    layers: Vec<LayerV1>,
+    search_delta: f32,
+    search_steps: u32,
+    translation: [f32; 3],
+    rotation: [f32; 4],
+    scale: [f32; 3],
+    path_cache: Option<PathCacheV1>,
+    hierarchy_cluster_size: usize,
+    /// Traversal cost and flags of each polygon, per layer. Empty for layers without areas.
+    areas: Vec<Vec<(f32, u32)>>,
+    /// Off mesh links set directly, empty when they were added by each layer.
+    links: Vec<LinkV1>,
+    /// Off mesh links added by each layer, so that they are kept when a layer is rebuilt.
+    links_by_layer: Vec<Vec<LinkV1>>,
+    closed_gates: u32,
+    weighted_paths: bool,
+    /// Variants for other agent radii, from radius class `1`. Always empty for the variants themselves.
+    variants: Vec<NavMeshV1>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LinkV1 {
+    start: [f32; 2],
+    end: [f32; 2],
+    cost: f32,
+    bidirectional: bool,
+    flags: u32,
+}
+
+impl From<&NavMeshLink> for LinkV1 {
+    fn from(link: &NavMeshLink) -> Self {
+        LinkV1 {
+            start: link.start.to_array(),
+            end: link.end.to_array(),
+            cost: link.cost,
+            bidirectional: link.bidirectional,
+            flags: link.flags,
+        }
+    }
+}
+
+impl From<LinkV1> for NavMeshLink {
+    fn from(link: LinkV1) -> Self {
+        NavMeshLink {
+            start: Vec2::from_array(link.start),
+            end: Vec2::from_array(link.end),
+            cost: link.cost,
+            bidirectional: link.bidirectional,
+            flags: link.flags,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct LayerV1 {
+    /// Coordinates and polygons of each vertex. Polygons from other layers are kept as stitched by polyanya.
+    vertices: Vec<([f32; 2], Vec<u32>)>,
+    /// Vertices of each polygon, and whether it's one way.
+    polygons: Vec<(Vec<u32>, bool)>,
+    offset: [f32; 2],
+    /// Only used with the `detailed-layers` feature, but always kept so that files are the same.
+    scale: [f32; 2],
+}
+
+#[derive(Serialize, Deserialize)]
+struct PathCacheV1 {
+    capacity: usize,
+}
+
+impl NavMesh {
+    fn to_versioned(&self) -> VersionedNavMesh {
+        VersionedNavMesh::V1(self.to_v1())
+    }
+
+    fn to_v1(&self) -> NavMeshV1 {
+        NavMeshV1 {
+            layers: self
+                .mesh
+                .layers
+                .iter()
+                .map(|layer| LayerV1 {
+                    vertices: layer
+                        .vertices
+                        .iter()
+                        .map(|vertex| (vertex.coords.to_array(), vertex.polygons.clone()))
+                        .collect(),
+                    polygons: layer
+                        .polygons
+                        .iter()
+                        .map(|polygon| (polygon.vertices.clone(), polygon.is_one_way))
+                        .collect(),
+                    offset: layer.offset.to_array(),
+                    #[cfg(feature = "detailed-layers")]
+                    scale: layer.scale.to_array(),
+                    #[cfg(not(feature = "detailed-layers"))]
+                    scale: [1.0, 1.0],
+                })
+                .collect(),
+            search_delta: self.mesh.search_delta,
+            search_steps: self.mesh.search_steps,
+            translation: self.transform.translation.to_array(),
+            rotation: self.transform.rotation.to_array(),
+            scale: self.transform.scale.to_array(),
+            path_cache: self.path_cache().map(|settings| PathCacheV1 {
+                capacity: settings.capacity,
+            }),
+            hierarchy_cluster_size: self.hierarchy_cluster_size,
+            areas: self
+                .areas
+                .iter()
+                .map(|layer| layer.iter().map(|area| (area.cost, area.flags)).collect())
+                .collect(),
+            links: if self.links_by_layer.is_empty() {
+                self.links.iter().map(LinkV1::from).collect()
+            } else {
+                vec![]
+            },
+            links_by_layer: self
+                .links_by_layer
+                .iter()
+                .map(|links| links.iter().map(LinkV1::from).collect())
+                .collect(),
+            closed_gates: self.closed_gates,
+            weighted_paths: self.weighted_paths,
+            variants: self.variants.iter().map(NavMesh::to_v1).collect(),
+        }
+    }
+
+    fn from_versioned(versioned: VersionedNavMesh) -> Result<NavMesh, MeshError> {
+        match versioned {
+            VersionedNavMesh::V1(navmesh) => NavMesh::from_v1(navmesh),
+        }
+    }
+
+    fn from_v1(
+        NavMeshV1 {
+            layers: serialized_layers,
+            search_delta,
+            search_steps,
+            translation,
+            rotation,
+            scale,
+            path_cache,
+            hierarchy_cluster_size,
+            areas,
+            links,
+            links_by_layer,
+            closed_gates,
+            weighted_paths,
+            variants,
+        }: NavMeshV1,
+    ) -> Result<NavMesh, MeshError> {
+        let sizes = serialized_layers
+            .iter()
+            .map(|layer| layer.polygons.len())
+            .collect::<Vec<_>>();
+        let single_layer = sizes.len() == 1;
+        if areas.len() > sizes.len()
+            || areas
+                .iter()
+                .zip(&sizes)
+                .any(|(areas, size)| !areas.is_empty() && areas.len() != *size)
+        {
+            return Err(MeshError::InvalidMesh);
+        }
+        let mut layers = Vec::with_capacity(serialized_layers.len());
+        for layer in serialized_layers {
+            if layer.polygons.len() >= 1 << 24 {
+                return Err(MeshError::TooManyPolygons);
+            }
+            let vertex_count = layer.vertices.len();
+            if layer
+                .polygons
+                .iter()
+                .flat_map(|(vertices, _)| vertices)
+                .any(|vertex| *vertex as usize >= vertex_count)
+            {
+                return Err(MeshError::InvalidMesh);
+            }
+            // Polygon indices are `(layer << 24) | polygon`, the layer is 0 on layers not stitched
+            if layer
+                .vertices
+                .iter()
+                .flat_map(|(_, polygons)| polygons)
+                .filter(|polygon| **polygon != u32::MAX)
+                .any(|polygon| {
+                    sizes
+                        .get((polygon >> 24) as usize)
+                        .is_none_or(|size| (polygon & 0xFFFFFF) as usize >= *size)
+                })
+            {
+                return Err(MeshError::InvalidMesh);
+            }
+
+            let mut baked = Layer::default();
+            baked.vertices = layer
+                .vertices
+                .into_iter()
+                .map(|(coords, polygons)| Vertex::new(Vec2::from_array(coords), polygons))
+                .collect();
+            baked.polygons = layer
+                .polygons
+                .into_iter()
+                .map(|(vertices, is_one_way)| Polygon::new(vertices, is_one_way))
+                .collect();
+            baked.offset = Vec2::from_array(layer.offset);
+            #[cfg(feature = "detailed-layers")]
+            {
+                baked.scale = Vec2::from_array(layer.scale);
+            }
+            // Islands are only used by polyanya on meshes with a single layer, and can't be computed
+            // on stitched layers
+            if single_layer {
+                baked.bake();
+            } else {
+                baked.bake_polygon_finder();
+            }
+            layers.push(baked);
+        }
+
+        let mut navmesh = NavMesh::from_polyanya_mesh(polyanya::Mesh {
+            layers,
+            search_delta,
+            search_steps,
+        });
+        navmesh.transform = Transform {
+            translation: Vec3::from_array(translation),
+            rotation: Quat::from_array(rotation),
+            scale: Vec3::from_array(scale),
+        };
+        navmesh.set_path_cache(path_cache.map(|settings| PathCacheSettings {
+            capacity: settings.capacity,
+        }));
+        navmesh.hierarchy_cluster_size = hierarchy_cluster_size;
+        navmesh.set_areas(
+            areas
+                .into_iter()
+                .map(|layer| {
+                    layer
+                        .into_iter()
+                        .map(|(cost, flags)| NavMeshArea { cost, flags })
+                        .collect()
+                })
+                .collect(),
+        );
+        if links_by_layer.is_empty() {
+            navmesh.set_links(links.into_iter().map(NavMeshLink::from).collect());
+        } else {
+            navmesh.set_links_by_layer(
+                links_by_layer
+                    .into_iter()
+                    .map(|links| links.into_iter().map(NavMeshLink::from).collect())
+                    .collect(),
+            );
+        }
+        navmesh.closed_gates = closed_gates;
+        navmesh.set_weighted_paths(weighted_paths);
+        navmesh.variants = variants
+            .into_iter()
+            .map(NavMesh::from_v1)
+            .collect::<Result<_, _>>()?;
+        Ok(navmesh)
+    }
+
+    /// Serializes the complete state of this [`NavMesh`] in the RON format, that can be read by [`NavMesh::from_ron`].
+    ///
+    /// This keeps all layers with their offsets and stitches, the areas of their polygons, the off mesh links, the closed gates, the [`NavMesh::transform`],
+    /// the search settings, if weighted paths are enabled, the settings of the path cache and the variants for other agent radii. Cached paths are not kept.
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(&self.to_versioned(), ron::ser::PrettyConfig::default())
+            .expect("a NavMesh can always be serialized")
+    }
+
+    /// Reads a [`NavMesh`] serialized with [`NavMesh::to_ron`].
+    pub fn from_ron(ron: &str) -> Result<NavMesh, NavMeshDeserializeError> {
+        let versioned = ron::from_str(ron).map_err(NavMeshDeserializeError::Ron)?;
+        NavMesh::from_versioned(versioned).map_err(NavMeshDeserializeError::MeshError)
+    }
+
+    /// Serializes the complete state of this [`NavMesh`] in a compact binary format, that can be read by
+    /// [`NavMesh::from_binary`].
+    ///
+    /// This keeps the same data as [`NavMesh::to_ron`].
+    pub fn to_binary(&self) -> Vec<u8> {
+        bincode::serde::encode_to_vec(self.to_versioned(), bincode::config::standard())
+            .expect("a NavMesh can always be serialized")
+    }
+
+    /// Reads a [`NavMesh`] serialized with [`NavMesh::to_binary`].
+    pub fn from_binary(bytes: &[u8]) -> Result<NavMesh, NavMeshDeserializeError> {
+        let (versioned, _) = bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+            .map_err(NavMeshDeserializeError::Binary)?;
+        NavMesh::from_versioned(versioned).map_err(NavMeshDeserializeError::MeshError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+    use polyanya::Triangulation;
+
+    use super::*;
+    use crate::tests::square_with_hole;
+
+    fn assert_same(navmesh: &NavMesh, reloaded: &NavMesh) {
+        assert_eq!(navmesh.transform(), reloaded.transform());
+        assert_eq!(navmesh.search_delta(), reloaded.search_delta());
+        assert_eq!(navmesh.search_steps(), reloaded.search_steps());
+        assert_eq!(navmesh.path_cache(), reloaded.path_cache());
+        assert_eq!(navmesh.weighted_paths(), reloaded.weighted_paths());
+        assert_eq!(navmesh.to_ron(), reloaded.to_ron());
+        assert_eq!(navmesh.radius_classes(), reloaded.radius_classes());
+        for (from, to) in [
+            (vec2(1.0, 1.0), vec2(9.0, 9.0)),
+            (vec2(5.0, 1.0), vec2(5.0, 9.0)),
+            (vec2(1.0, 1.0), vec2(18.0, 5.0)),
+        ] {
+            assert_eq!(navmesh.path(from, to), reloaded.path(from, to));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut navmesh = square_with_hole();
+        navmesh.set_transform(Transform::from_xyz(1.0, 2.0, 3.0).with_scale(Vec3::splat(2.0)));
+        navmesh.set_search_delta(0.5);
+        navmesh.set_path_cache(Some(PathCacheSettings::default()));
+        let polygons = navmesh.get().layers[0].polygons.len();
+        navmesh.set_layer_areas(
+            0,
+            (0..polygons)
+                .map(|i| NavMeshArea {
+                    cost: 1.0 + i as f32,
+                    flags: 1 << i,
+                })
+                .collect(),
+        );
+        navmesh.set_links(vec![NavMeshLink {
+            start: vec2(1.0, 1.0),
+            end: vec2(9.0, 9.0),
+            cost: 2.0,
+            bidirectional: false,
+            flags: 3,
+        }]);
+        navmesh.close_gates(1 << 4);
+        navmesh.set_weighted_paths(true);
+        navmesh.set_radius_variants(vec![NavMesh::from_edge_and_obstacles(
+            vec![
+                vec2(1.0, 1.0),
+                vec2(9.0, 1.0),
+                vec2(9.0, 9.0),
+                vec2(1.0, 9.0),
+            ],
+            vec![],
+        )]);
+
+        assert_same(&navmesh, &NavMesh::from_ron(&navmesh.to_ron()).unwrap());
+        assert_same(
+            &navmesh,
+            &NavMesh::from_binary(&navmesh.to_binary()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn round_trip_stitched_layers() {
+        let square = |offset: Vec2| {
+            let mut layer = Triangulation::from_outer_edges(&[
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(10.0, 10.0),
+                vec2(0.0, 10.0),
+            ])
+            .as_layer();
+            layer.offset = offset;
+            layer
+        };
+        let mut mesh = polyanya::Mesh {
+            layers: vec![square(Vec2::ZERO), square(vec2(10.0, 0.0))],
+            ..Default::default()
+        };
+        mesh.stitch_at_points(
+            vec![((0, 1), vec![vec2(10.0, 0.0), vec2(10.0, 10.0)])],
+            false,
+        );
+        let navmesh = NavMesh::from_polyanya_mesh(mesh);
+        assert!(navmesh.path(vec2(1.0, 1.0), vec2(18.0, 5.0)).is_some());
+
+        let reloaded = NavMesh::from_binary(&navmesh.to_binary()).unwrap();
+        assert_same(&navmesh, &reloaded);
+        assert_same(&navmesh, &NavMesh::from_ron(&navmesh.to_ron()).unwrap());
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert!(matches!(
+            NavMesh::from_ron("V1(())"),
+            Err(NavMeshDeserializeError::Ron(_))
+        ));
+        assert!(matches!(
+            NavMesh::from_binary(&[42]),
+            Err(NavMeshDeserializeError::Binary(_))
+        ));
+
+        let navmesh = NavMesh::from_edge_and_obstacles(
+            vec![vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(1.0, 1.0)],
+            vec![],
+        );
+        let VersionedNavMesh::V1(mut serialized) = navmesh.to_versioned();
+        serialized.areas = vec![vec![(1.0, 1), (2.0, 1)]];
+        assert!(matches!(
+            NavMesh::from_versioned(VersionedNavMesh::V1(serialized)),
+            Err(MeshError::InvalidMesh)
+        ));
+
+        let VersionedNavMesh::V1(mut serialized) = navmesh.to_versioned();
+        serialized.layers[0].polygons[0].0[0] = 42;
+        assert!(matches!(
+            NavMesh::from_versioned(VersionedNavMesh::V1(serialized)),
+            Err(MeshError::InvalidMesh)
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt::Write;
+
+use bevy::math::Vec2;
+use itertools::Itertools;
+use polyanya::Path;
+
+use crate::{NavMesh, topology};
+
+const LAYER_COLORS: [&str; 8] = [
+    "#8ecae6", "#ffb703", "#90be6d", "#f28482", "#cdb4db", "#f9c74f", "#84a59d", "#bde0fe",
+];
+
+/// What to draw in addition to the polygons of a [`NavMesh`] with [`NavMesh::to_svg_with`].
+///
+/// All coordinates are in mesh space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// Paths to draw, each one starting at its first point.
+    pub paths: Vec<Vec<Vec2>>,
+    /// Obstacles to draw, as polygons.
+    pub obstacles: Vec<Vec<Vec2>>,
+    /// Fill polygons with a different color for each layer. Otherwise all polygons have the same color.
+    pub layers: bool,
+    /// Draw the vertices of the mesh.
+    pub vertices: bool,
+    /// Draw the edges where layers are stitched together.
+    pub stitches: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            paths: vec![],
+            obstacles: vec![],
+            layers: true,
+            vertices: true,
+            stitches: true,
+        }
+    }
+}
+
+impl SvgOptions {
+    /// Adds a [`Path`] found from `from` to the paths to draw.
+    pub fn with_path(mut self, from: Vec2, path: &Path) -> Self {
+        self.paths.push(
+            std::iter::once(from)
+                .chain(path.path.iter().copied())
+                .collect(),
+        );
+        self
+    }
+
+    /// Adds an obstacle to draw.
+    pub fn with_obstacle(mut self, obstacle: Vec<Vec2>) -> Self {
+        self.obstacles.push(obstacle);
+        self
+    }
+}
+
+fn points(coords: &[Vec2]) -> String {
+    coords.iter().map(|c| format!("{},{}", c.x, c.y)).join(" ")
+}
+
+impl NavMesh {
+    /// Renders the polygons, vertices and layer stitches of this [`NavMesh`] to an SVG string, in mesh space.
+    ///
+    /// This doesn't need a window or a GPU, and can be used to debug a [`NavMesh`] in tests or bug reports.
+    pub fn to_svg(&self) -> String {
+        self.to_svg_with(&SvgOptions::default())
+    }
+
+    /// Renders this [`NavMesh`] to an SVG string, in mesh space, with the overlays from `options`.
+    pub fn to_svg_with(&self, options: &SvgOptions) -> String {
+        let polygons = topology::polygons(&self.mesh)
+            .map(|polygon| (polygon, topology::polygon_coords(&self.mesh, polygon)))
+            .filter(|(_, coords)| coords.len() >= 3)
+            .collect::<Vec<_>>();
+
+        let (min, max) = polygons
+            .iter()
+            .flat_map(|(_, coords)| coords.iter())
+            .chain(options.obstacles.iter().flatten())
+            .chain(options.paths.iter().flatten())
+            .fold((Vec2::MAX, Vec2::MIN), |(min, max), c| {
+                (min.min(*c), max.max(*c))
+            });
+        let (min, max) = if min.cmple(max).all() {
+            (min, max)
+        } else {
+            (Vec2::ZERO, Vec2::ONE)
+        };
+        let size = (max - min).max_element().max(f32::EPSILON);
+        let margin = size * 0.02;
+        let radius = size * 0.004;
+
+        let mut svg = String::new();
+        // The y axis is flipped so that the SVG is oriented like the mesh
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            min.x - margin,
+            -max.y - margin,
+            max.x - min.x + margin * 2.0,
+            max.y - min.y + margin * 2.0,
+        );
+        let _ = writeln!(svg, r#"<g transform="scale(1,-1)">"#);
+
+        let _ = writeln!(svg, r#"<g id="polygons">"#);
+        for (polygon, coords) in &polygons {
+            let fill = if options.layers {
+                LAYER_COLORS[polygon.layer as usize % LAYER_COLORS.len()]
+            } else {
+                LAYER_COLORS[0]
+            };
+            let _ = writeln!(
+                svg,
+                r#"<polygon points="{}" fill="{fill}" stroke="black" stroke-width="1" vector-effect="non-scaling-stroke"/>"#,
+                points(coords)
+            );
+        }
+        let _ = writeln!(svg, "</g>");
+
+        if options.stitches {
+            let _ = writeln!(svg, r#"<g id="stitches">"#);
+            for (polygon, coords) in &polygons {
+                for edge in 0..coords.len() {
+                    if let Some(neighbour) = topology::neighbour(&self.mesh, *polygon, edge)
+                        && neighbour.layer != polygon.layer
+                    {
+                        let (a, b) = (coords[edge], coords[(edge + 1) % coords.len()]);
+                        let _ = writeln!(
+                            svg,
+                            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="red" stroke-width="3" vector-effect="non-scaling-stroke"/>"#,
+                            a.x, a.y, b.x, b.y
+                        );
+                    }
+                }
+            }
+            let _ = writeln!(svg, "</g>");
+        }
+
+        if options.vertices {
+            let _ = writeln!(svg, r#"<g id="vertices">"#);
+            for layer in &self.mesh.layers {
+                for vertex in &layer.vertices {
+                    let coords = vertex.coords + layer.offset;
+                    let _ = writeln!(
+                        svg,
+                        r#"<circle cx="{}" cy="{}" r="{radius}" fill="black"/>"#,
+                        coords.x, coords.y
+                    );
+                }
+            }
+            let _ = writeln!(svg, "</g>");
+        }
+
+        if !options.obstacles.is_empty() {
+            let _ = writeln!(svg, r#"<g id="obstacles">"#);
+            for obstacle in &options.obstacles {
+                let _ = writeln!(
+                    svg,
+                    r##"<polygon points="{}" fill="#555555" fill-opacity="0.6" stroke="#333333" stroke-width="1" vector-effect="non-scaling-stroke"/>"##,
+                    points(obstacle)
+                );
+            }
+            let _ = writeln!(svg, "</g>");
+        }
+
+        if !options.paths.is_empty() {
+            let _ = writeln!(svg, r#"<g id="paths">"#);
+            for path in &options.paths {
+                let _ = writeln!(
+                    svg,
+                    r#"<polyline points="{}" fill="none" stroke="blue" stroke-width="2" vector-effect="non-scaling-stroke"/>"#,
+                    points(path)
+                );
+            }
+            let _ = writeln!(svg, "</g>");
+        }
+
+        let _ = writeln!(svg, "</g>");
+        let _ = writeln!(svg, "</svg>");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use super::*;
+    use crate::tests::square_with_hole;
+
+    #[test]
+    fn svg_with_overlays() {
+        let obstacle = vec![
+            vec2(4.0, 4.0),
+            vec2(6.0, 4.0),
+            vec2(6.0, 6.0),
+            vec2(4.0, 6.0),
+        ];
+        let navmesh = square_with_hole();
+        let polygon_count = navmesh.get().layers[0].polygons.len();
+        let vertex_count = navmesh.get().layers[0].vertices.len();
+
+        let svg = navmesh.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<polygon").count(), polygon_count);
+        assert_eq!(svg.matches("<circle").count(), vertex_count);
+        assert!(!svg.contains("<polyline"));
+
+        let from = vec2(1.0, 1.0);
+        let path = navmesh.path(from, vec2(9.0, 9.0)).unwrap();
+        let svg = navmesh.to_svg_with(
+            &SvgOptions {
+                vertices: false,
+                ..Default::default()
+            }
+            .with_path(from, &path)
+            .with_obstacle(obstacle),
+        );
+        assert_eq!(svg.matches("<polygon").count(), polygon_count + 1);
+        assert_eq!(svg.matches("<circle").count(), 0);
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert!(svg.contains("1,1 "));
+    }
+
+    #[test]
+    fn svg_with_stitches() {
+        let square = |offset: Vec2| {
+            let mut layer = polyanya::Triangulation::from_outer_edges(&[
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(10.0, 10.0),
+                vec2(0.0, 10.0),
+            ])
+            .as_layer();
+            layer.offset = offset;
+            layer
+        };
+        let mut mesh = polyanya::Mesh {
+            layers: vec![square(Vec2::ZERO), square(vec2(10.0, 0.0))],
+            ..Default::default()
+        };
+        mesh.stitch_at_points(
+            vec![((0, 1), vec![vec2(10.0, 0.0), vec2(10.0, 10.0)])],
+            false,
+        );
+        let svg = NavMesh::from_polyanya_mesh(mesh).to_svg();
+        // The shared edge is drawn once from each layer
+        assert_eq!(svg.matches("<line").count(), 2);
+        assert!(svg.contains(r#"x1="10""#));
+    }
+}
@@ -0,0 +1,366 @@
+//! Support for Tiled maps loaded with `bevy_ecs_tiled`.
+
+use bevy::{math::vec2, platform::collections::HashMap, prelude::*};
+use bevy_ecs_tiled::prelude::*;
+use parry2d::{
+    math::{Isometry, Real},
+    shape::SharedShape,
+};
+use polyanya::Triangulation;
+use tiled::{ObjectLayerData, ObjectShape};
+
+use crate::{
+    obstacles::{cached::CachedObstacle, parry2d::shape::SharedShapeStorage},
+    updater::{
+        CachableObstacle, ManagedNavMesh, NavMeshSettings, NavMeshUpdateMode, NavmeshUpdaterPlugin,
+    },
+};
+
+/// Settings of the [`NavMesh`](crate::NavMesh) spawned for each Tiled map by [`TiledNavMeshPlugin`].
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct TiledNavMeshSettings {
+    /// See [`NavMeshSettings::simplify`]. The default value is `0.2`, to avoid very small geometry from round obstacles.
+    pub simplify: f32,
+    /// See [`NavMeshSettings::merge_steps`]. The default value is `2`.
+    pub merge_steps: usize,
+    /// See [`NavMeshSettings::agent_radius`]. The default value is `0.0`.
+    pub agent_radius: f32,
+    /// When to update the [`NavMesh`](crate::NavMesh). The default value is [`NavMeshUpdateMode::Direct`].
+    pub update_mode: NavMeshUpdateMode,
+}
+
+impl Default for TiledNavMeshSettings {
+    fn default() -> Self {
+        Self {
+            simplify: 0.2,
+            merge_steps: 2,
+            agent_radius: 0.0,
+            update_mode: NavMeshUpdateMode::Direct,
+        }
+    }
+}
+
+/// Marker component for a [`NavMesh`](crate::NavMesh) spawned by [`TiledNavMeshPlugin`] for a Tiled map.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TiledNavMesh {
+    /// Entity of the map.
+    pub map: Entity,
+}
+
+/// Bevy plugin that builds a [`NavMesh`](crate::NavMesh) for each map spawned from a `TiledMapHandle`.
+///
+/// The colliders of the map are spawned as [`CachedObstacle<SharedShapeStorage>`] obstacles, and a
+/// [`ManagedNavMesh`] covering the map is spawned as a child of the map entity with a [`TiledNavMesh`] component.
+///
+/// This plugin needs the `TiledMapPlugin` from `bevy_ecs_tiled`, and adds the [`NavmeshUpdaterPlugin`] for
+/// [`CachedObstacle<SharedShapeStorage>`] if it's not already present. Colliders are spawned for the objects
+/// named by `TiledPhysicsSettings`, which can be added on the map entity.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TiledNavMeshPlugin {
+    /// Settings of the spawned [`NavMesh`](crate::NavMesh)es.
+    pub settings: TiledNavMeshSettings,
+}
+
+impl Plugin for TiledNavMeshPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<NavmeshUpdaterPlugin<CachedObstacle<SharedShapeStorage>>>() {
+            app.add_plugins(NavmeshUpdaterPlugin::<CachedObstacle<SharedShapeStorage>>::default());
+        }
+        app.insert_resource(self.settings)
+            .add_plugins(TiledPhysicsPlugin::<TiledObstaclesBackend>::default())
+            .add_observer(spawn_tiled_navmesh);
+    }
+}
+
+fn spawn_tiled_navmesh(
+    trigger: Trigger<TiledMapCreated>,
+    mut commands: Commands,
+    maps: Res<Assets<TiledMap>>,
+    anchors: Query<&TilemapAnchor>,
+    settings: Res<TiledNavMeshSettings>,
+) {
+    let Some(tiled_map) = maps.get(trigger.asset_id) else {
+        return;
+    };
+    let anchor = anchors.get(trigger.entity).copied().unwrap_or_default();
+    let grid_size = get_grid_size(&tiled_map.map);
+    let size = vec2(
+        tiled_map.tilemap_size.x as f32 * tiled_map.map.tile_width as f32,
+        tiled_map.tilemap_size.y as f32 * tiled_map.map.tile_height as f32,
+    );
+    // Layers are placed at the center of their bottom left tile, the navmesh starts at its corner
+    let origin = tiled_map.offset(&anchor) - Vec2::new(grid_size.x, grid_size.y) / 2.0;
+
+    commands.spawn((
+        ManagedNavMesh::from_id(trigger.entity.to_bits() as u128),
+        NavMeshSettings {
+            fixed: Triangulation::from_outer_edges(&[
+                vec2(0.0, 0.0),
+                vec2(size.x, 0.0),
+                size,
+                vec2(0.0, size.y),
+            ]),
+            simplify: settings.simplify,
+            merge_steps: settings.merge_steps,
+            agent_radius: settings.agent_radius,
+            ..default()
+        },
+        settings.update_mode,
+        Transform::from_translation(origin.extend(0.0)),
+        TiledNavMesh {
+            map: trigger.entity,
+        },
+        ChildOf(trigger.entity),
+    ));
+}
+
+/// Physics backend for `bevy_ecs_tiled` that spawns the colliders of a map as [`CachedObstacle<SharedShapeStorage>`].
+///
+/// Tiles colliders are merged in a compound shape for each user type. This is added by [`TiledNavMeshPlugin`].
+#[derive(Default, Debug, Clone, Copy, Reflect)]
+#[reflect(Default, Debug)]
+pub struct TiledObstaclesBackend;
+
+fn spawn_obstacle(commands: &mut Commands, shape: SharedShape) -> Entity {
+    commands
+        .spawn((
+            CachedObstacle::<SharedShapeStorage>::new(SharedShapeStorage::from(shape)),
+            CachableObstacle,
+        ))
+        .id()
+}
+
+fn spawn_composed(
+    commands: &mut Commands,
+    composables: HashMap<String, Vec<(Isometry<Real>, SharedShape)>>,
+    spawn_infos: &mut Vec<TiledColliderSpawnInfos>,
+) {
+    for (user_type, composables) in composables {
+        spawn_infos.push(TiledColliderSpawnInfos {
+            name: format!("{}[ComposedTile]", user_type),
+            entity: spawn_obstacle(commands, SharedShape::compound(composables)),
+            transform: Transform::default(),
+        });
+    }
+}
+
+impl TiledPhysicsBackend for TiledObstaclesBackend {
+    fn spawn_colliders(
+        &self,
+        commands: &mut Commands,
+        tiled_map: &TiledMap,
+        filter: &TiledNameFilter,
+        collider: &TiledCollider,
+        anchor: &TilemapAnchor,
+    ) -> Vec<TiledColliderSpawnInfos> {
+        match collider {
+            TiledCollider::Object {
+                layer_id: _,
+                object_id: _,
+            } => {
+                let Some(object) = collider.get_object(tiled_map) else {
+                    return vec![];
+                };
+
+                match object.get_tile() {
+                    Some(object_tile) => object_tile.get_tile().and_then(|tile| {
+                        let object_layer_data = tile.collision.as_ref()?;
+                        let mut composables = HashMap::new();
+                        let mut spawn_infos = vec![];
+                        compose_tiles(
+                            commands,
+                            filter,
+                            object_layer_data,
+                            Vec2::ZERO,
+                            get_grid_size(&tiled_map.map),
+                            &mut composables,
+                            &mut spawn_infos,
+                        );
+                        spawn_composed(commands, composables, &mut spawn_infos);
+                        Some(spawn_infos)
+                    }),
+                    None => get_position_and_shape(&object.shape).map(|(pos, shared_shape, _)| {
+                        let iso = Isometry3d::from_rotation(Quat::from_rotation_z(
+                            f32::to_radians(-object.rotation),
+                        )) * Isometry3d::from_xyz(pos.x, pos.y, 0.);
+
+                        vec![TiledColliderSpawnInfos {
+                            name: format!("Obstacle[Object={}]", object.name),
+                            entity: spawn_obstacle(commands, shared_shape),
+                            transform: Transform::from_isometry(iso),
+                        }]
+                    }),
+                }
+                .unwrap_or_default()
+            }
+
+            TiledCollider::TilesLayer { layer_id: _ } => {
+                let mut composables = HashMap::new();
+                let mut spawn_infos = vec![];
+                for (tile_position, tile) in collider.get_tiles(tiled_map, anchor) {
+                    if let Some(collision) = &tile.collision {
+                        compose_tiles(
+                            commands,
+                            filter,
+                            collision,
+                            tile_position,
+                            get_grid_size(&tiled_map.map),
+                            &mut composables,
+                            &mut spawn_infos,
+                        );
+                    }
+                }
+                spawn_composed(commands, composables, &mut spawn_infos);
+                spawn_infos
+            }
+        }
+    }
+}
+
+/// Collects the shapes of the colliders of a tile. Rectangles and ellipses are grouped by user type in
+/// `composables`, other shapes are spawned directly.
+fn compose_tiles(
+    commands: &mut Commands,
+    filter: &TiledNameFilter,
+    object_layer_data: &ObjectLayerData,
+    tile_offset: Vec2,
+    grid_size: TilemapGridSize,
+    composables: &mut HashMap<String, Vec<(Isometry<Real>, SharedShape)>>,
+    spawn_infos: &mut Vec<TiledColliderSpawnInfos>,
+) {
+    for object in object_layer_data.object_data() {
+        if !filter.contains(&object.name) {
+            continue;
+        }
+        let position = tile_offset
+            // Object position
+            + Vec2 {
+                x: object.x - grid_size.x / 2.,
+                y: (grid_size.y - object.y) - grid_size.y / 2.,
+            };
+        if let Some((shape_offset, shared_shape, is_composable)) =
+            get_position_and_shape(&object.shape)
+        {
+            if is_composable {
+                let iso_and_shape = (
+                    Isometry::<Real>::new(position.into(), f32::to_radians(-object.rotation))
+                        * Isometry::<Real>::new(shape_offset.into(), 0.),
+                    shared_shape,
+                );
+                composables
+                    .entry_ref(&object.user_type)
+                    .or_insert(vec![])
+                    .push(iso_and_shape);
+            } else {
+                let iso = Isometry3d::from_xyz(position.x, position.y, 0.)
+                    * Isometry3d::from_rotation(Quat::from_rotation_z(f32::to_radians(
+                        -object.rotation,
+                    )));
+
+                spawn_infos.push(TiledColliderSpawnInfos {
+                    name: "Obstacle[ComplexTile]".to_string(),
+                    entity: spawn_obstacle(commands, shared_shape),
+                    transform: Transform::from_isometry(iso),
+                });
+            }
+        }
+    }
+}
+
+/// Converts the shape of a Tiled object to a [`SharedShape`].
+///
+/// Returns the offset of the shape from the object position, the shape, and if it can be part of a compound shape.
+fn get_position_and_shape(shape: &ObjectShape) -> Option<(Vec2, SharedShape, bool)> {
+    match shape {
+        ObjectShape::Rect { width, height } => {
+            let shape = SharedShape::cuboid(width / 2., height / 2.);
+            let pos = Vec2::new(width / 2., -height / 2.);
+            Some((pos, shape, true))
+        }
+        ObjectShape::Ellipse { width, height } => {
+            let shape = if width > height {
+                SharedShape::capsule(
+                    Vec2::new((-width + height) / 2., 0.).into(),
+                    Vec2::new((width - height) / 2., 0.).into(),
+                    height / 2.,
+                )
+            } else {
+                SharedShape::capsule(
+                    Vec2::new(0., (-height + width) / 2.).into(),
+                    Vec2::new(0., (height - width) / 2.).into(),
+                    width / 2.,
+                )
+            };
+            let pos = Vec2::new(width / 2., -height / 2.);
+            Some((pos, shape, true))
+        }
+        ObjectShape::Polyline { points } => {
+            let vertices = points
+                .iter()
+                .map(|(x, y)| Vec2::new(*x, -*y))
+                .map(|v| v.into())
+                .collect();
+            let shape = SharedShape::polyline(vertices, None);
+            Some((Vec2::ZERO, shape, false))
+        }
+        ObjectShape::Polygon { points } => {
+            if points.len() < 3 {
+                return None;
+            }
+
+            let vertices = points
+                .iter()
+                .map(|(x, y)| Vec2::new(*x, -*y))
+                .map(|v| v.into())
+                .collect();
+            let indices = (0..points.len() as u32 - 1)
+                .map(|i| [i, i + 1])
+                .chain([[points.len() as u32 - 1, 0]])
+                .collect();
+            let shape = SharedShape::polyline(vertices, Some(indices));
+            Some((Vec2::ZERO, shape, false))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_shapes() {
+        let (offset, rect, composable) = get_position_and_shape(&ObjectShape::Rect {
+            width: 4.0,
+            height: 2.0,
+        })
+        .unwrap();
+        assert_eq!(offset, vec2(2.0, -1.0));
+        assert!(composable);
+        let half_extents = rect.as_cuboid().unwrap().half_extents;
+        assert_eq!((half_extents.x, half_extents.y), (2.0, 1.0));
+
+        let (_, ellipse, composable) = get_position_and_shape(&ObjectShape::Ellipse {
+            width: 2.0,
+            height: 6.0,
+        })
+        .unwrap();
+        assert!(composable);
+        assert_eq!(ellipse.as_capsule().unwrap().radius, 1.0);
+
+        let (offset, polygon, composable) = get_position_and_shape(&ObjectShape::Polygon {
+            points: vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0)],
+        })
+        .unwrap();
+        assert_eq!(offset, Vec2::ZERO);
+        assert!(!composable);
+        assert_eq!(polygon.as_polyline().unwrap().segments().count(), 3);
+
+        assert!(
+            get_position_and_shape(&ObjectShape::Polygon {
+                points: vec![(0.0, 0.0), (2.0, 0.0)],
+            })
+            .is_none()
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Helpers to navigate the polygons of a [`polyanya::Mesh`], across its layers.
+
+use bevy::math::Vec2;
+use bvh2d::{
+    aabb::{AABB, Bounded},
+    bvh2d::BVH2d,
+};
+use polyanya::Mesh;
+
+use crate::NavMesh;
+
+/// Distance under which a point is considered to be on an edge.
+pub(crate) const EPSILON: f32 = 1.0e-4;
+
+/// A polygon in a [`NavMesh`](crate::NavMesh), identified by its layer and its index in that layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PolygonRef {
+    /// Layer of the polygon.
+    pub layer: u8,
+    /// Index of the polygon in its layer.
+    pub polygon: u32,
+}
+
+impl PolygonRef {
+    /// Decodes a polygon index as stored in [`polyanya::Vertex::polygons`].
+    ///
+    /// Once layers are stitched, the layer is stored in the 8 highest bits of the index.
+    #[inline]
+    pub(crate) fn from_neighbour(index: u32) -> Option<Self> {
+        (index != u32::MAX).then_some(PolygonRef {
+            layer: (index >> 24) as u8,
+            polygon: index & 0x00FF_FFFF,
+        })
+    }
+
+    /// Encodes this polygon as it would be stored in [`polyanya::Vertex::polygons`].
+    #[inline]
+    pub(crate) fn as_neighbour(&self) -> u32 {
+        ((self.layer as u32) << 24) | self.polygon
+    }
+}
+
+/// Coordinates of the vertices of a polygon, in mesh space.
+///
+/// Returns an empty list if the polygon doesn't exist.
+pub(crate) fn polygon_coords(mesh: &Mesh, polygon: PolygonRef) -> Vec<Vec2> {
+    let Some(layer) = mesh.layers.get(polygon.layer as usize) else {
+        return vec![];
+    };
+    let Some(inner) = layer.polygons.get(polygon.polygon as usize) else {
+        return vec![];
+    };
+    inner
+        .vertices
+        .iter()
+        .filter_map(|v| layer.vertices.get(*v as usize))
+        .map(|v| v.coords + layer.offset)
+        .collect()
+}
+
+/// Checks if a point is inside a convex polygon with its vertices in counter clockwise order.
+///
+/// Points on the edges are considered inside.
+pub(crate) fn contains(coords: &[Vec2], point: Vec2) -> bool {
+    if coords.len() < 3 {
+        return false;
+    }
+    coords
+        .iter()
+        .zip(coords.iter().cycle().skip(1))
+        .all(|(a, b)| {
+            let edge = *b - *a;
+            edge.perp_dot(point - *a) >= -EPSILON * edge.length()
+        })
+}
+
+/// Checks if a point is inside a polygon, that can be concave.
+pub(crate) fn inside(polygon: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    for (a, b) in polygon.iter().zip(polygon.iter().cycle().skip(1)) {
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Bounding box of a polygon, slightly enlarged so that points on its edges are found.
+struct BoundedPolygon(Vec2, Vec2);
+
+impl Bounded for BoundedPolygon {
+    fn aabb(&self) -> AABB {
+        AABB::with_bounds(self.0, self.1)
+    }
+}
+
+/// The polygons of each layer of a mesh in a BVH, to find which one contains a point.
+#[derive(Debug, Clone)]
+pub(crate) struct PolygonFinder {
+    layers: Vec<BVH2d>,
+    /// Bounding box of each polygon of each layer, in the space of the layer.
+    bounds: Vec<Vec<(Vec2, Vec2)>>,
+}
+
+impl PolygonFinder {
+    pub(crate) fn new(mesh: &Mesh) -> Self {
+        let bounds = mesh
+            .layers
+            .iter()
+            .map(|layer| {
+                layer
+                    .polygons
+                    .iter()
+                    .map(|polygon| {
+                        polygon
+                            .vertices
+                            .iter()
+                            .filter_map(|v| layer.vertices.get(*v as usize))
+                            .fold((Vec2::MAX, Vec2::MIN), |(min, max), v| {
+                                (min.min(v.coords), max.max(v.coords))
+                            })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let layers = bounds
+            .iter()
+            .map(|layer| {
+                let bounds = layer
+                    .iter()
+                    .map(|(min, max)| BoundedPolygon(*min - EPSILON, *max + EPSILON))
+                    .collect::<Vec<_>>();
+                BVH2d::build(&bounds)
+            })
+            .collect();
+        PolygonFinder { layers, bounds }
+    }
+
+    /// Bounding box of a polygon, in mesh space.
+    ///
+    /// Returns `None` if the polygon doesn't exist or has no vertices.
+    pub(crate) fn bounds(&self, mesh: &Mesh, polygon: PolygonRef) -> Option<(Vec2, Vec2)> {
+        let offset = mesh.layers.get(polygon.layer as usize)?.offset;
+        let (min, max) = *self
+            .bounds
+            .get(polygon.layer as usize)?
+            .get(polygon.polygon as usize)?;
+        min.cmple(max).all().then_some((min + offset, max + offset))
+    }
+
+    /// Finds the polygon containing a point, in mesh space.
+    ///
+    /// Layers are searched in order, the first polygon found is returned.
+    pub(crate) fn locate(&self, mesh: &Mesh, point: Vec2) -> Option<PolygonRef> {
+        mesh.layers
+            .iter()
+            .zip(&self.layers)
+            .enumerate()
+            .find_map(|(layer_index, (layer, bvh))| {
+                let local = point - layer.offset;
+                bvh.contains_iterator(&local)
+                    .find(|polygon| {
+                        let coords = layer.polygons[*polygon]
+                            .vertices
+                            .iter()
+                            .filter_map(|v| layer.vertices.get(*v as usize))
+                            .map(|v| v.coords)
+                            .collect::<Vec<_>>();
+                        contains(&coords, local)
+                    })
+                    .map(|polygon| PolygonRef {
+                        layer: layer_index as u8,
+                        polygon: polygon as u32,
+                    })
+            })
+    }
+}
+
+/// Polygons around a vertex of a layer, including polygons from stitched layers.
+pub(crate) fn vertex_polygons(
+    mesh: &Mesh,
+    layer: u8,
+    vertex: u32,
+) -> impl Iterator<Item = PolygonRef> + '_ {
+    mesh.layers
+        .get(layer as usize)
+        .and_then(|layer| layer.vertices.get(vertex as usize))
+        .into_iter()
+        .flat_map(|vertex| vertex.polygons.iter())
+        .filter_map(|index| PolygonRef::from_neighbour(*index))
+}
+
+/// The polygon on the other side of the edge starting at the `edge`-th vertex of `polygon`, if any.
+pub(crate) fn neighbour(mesh: &Mesh, polygon: PolygonRef, edge: usize) -> Option<PolygonRef> {
+    let layer = mesh.layers.get(polygon.layer as usize)?;
+    let vertices = &layer.polygons.get(polygon.polygon as usize)?.vertices;
+    let start = layer.vertices.get(*vertices.get(edge)? as usize)?;
+    let end = layer
+        .vertices
+        .get(vertices[(edge + 1) % vertices.len()] as usize)?;
+    let own = polygon.as_neighbour();
+    start
+        .polygons
+        .iter()
+        .filter(|index| **index != u32::MAX && **index != own)
+        .find(|index| end.polygons.contains(index))
+        .and_then(|index| PolygonRef::from_neighbour(*index))
+}
+
+/// All the polygons of a mesh, in all its layers.
+pub(crate) fn polygons(mesh: &Mesh) -> impl Iterator<Item = PolygonRef> + '_ {
+    mesh.layers
+        .iter()
+        .enumerate()
+        .flat_map(|(layer_index, layer)| {
+            (0..layer.polygons.len() as u32).map(move |polygon| PolygonRef {
+                layer: layer_index as u8,
+                polygon,
+            })
+        })
+}
+
+/// Closest point to `point` on the segment between `a` and `b`.
+pub(crate) fn closest_on_segment(point: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let segment = b - a;
+    let length_squared = segment.length_squared();
+    if length_squared == 0.0 {
+        return a;
+    }
+    a + segment * ((point - a).dot(segment) / length_squared).clamp(0.0, 1.0)
+}
+
+/// Area of a polygon with its vertices in counter clockwise order.
+pub(crate) fn area(coords: &[Vec2]) -> f32 {
+    coords
+        .iter()
+        .zip(coords.iter().cycle().skip(1))
+        .map(|(a, b)| a.perp_dot(*b))
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Centroid of a polygon.
+pub(crate) fn centroid(coords: &[Vec2]) -> Vec2 {
+    let (weighted, area) = coords.iter().zip(coords.iter().cycle().skip(1)).fold(
+        (Vec2::ZERO, 0.0),
+        |(weighted, area), (a, b)| {
+            let cross = a.perp_dot(*b);
+            (weighted + (*a + *b) * cross, area + cross)
+        },
+    );
+    if area.abs() > f32::EPSILON {
+        weighted / (3.0 * area)
+    } else {
+        coords.iter().copied().sum::<Vec2>() / coords.len().max(1) as f32
+    }
+}
+
+impl NavMesh {
+    pub(crate) fn polygon_finder(&self) -> &PolygonFinder {
+        self.polygon_finder
+            .get_or_init(|| PolygonFinder::new(&self.mesh))
+    }
+
+    /// Finds the polygon containing a point, in mesh space.
+    ///
+    /// Layers are searched in order, the first polygon found is returned. Polygons are indexed in a BVH
+    /// the first time this is needed, then it's kept with the mesh.
+    pub(crate) fn locate(&self, point: Vec2) -> Option<PolygonRef> {
+        self.polygon_finder().locate(&self.mesh, point)
+    }
+}
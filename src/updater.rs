@@ -1,6 +1,6 @@
 use std::{
     marker::PhantomData,
-    sync::{Arc, RwLock},
+    sync::{Arc, OnceLock, RwLock},
     time::Duration,
 };
 
@@ -18,7 +18,13 @@ use bevy::{
 };
 use polyanya::{Layer, Mesh, Triangulation};
 
-use crate::{NavMesh, obstacles::ObstacleSource};
+use crate::{
+    NavMesh, PathCacheSettings,
+    areas::{self, NavMeshArea},
+    links::{NavMeshLink, OffMeshLink},
+    obstacles::ObstacleSource,
+    world_to_mesh,
+};
 use rayon::prelude::*;
 /// A Marker component for an obstacle that can be cached.
 ///
@@ -137,14 +143,32 @@ pub struct NavMeshSettings {
     pub scale: Vec2,
     /// The radius of the agent used to inflate obstacles in the [`NavMesh`].
     pub agent_radius: f32,
+    /// Radii of other kinds of agents. A variant of the [`NavMesh`] is built for each of them, sharing the
+    /// processing of obstacles with the main one. The default value is empty.
+    ///
+    /// Variants are available from [`NavMesh::radius_class`]: class `0` is the [`NavMesh`] built with
+    /// [`Self::agent_radius`], and class `i + 1` the one built with `additional_agent_radii[i]`.
+    ///
+    /// Variants are only built when [`Self::layer`] is `None`, a warning is logged otherwise.
+    pub additional_agent_radii: Vec<f32>,
     /// Determines if the agent radius should be applied to the outer edges of the [`NavMesh`].
     ///
     /// When using layers, applying the agent radius to outer edges can block stitching them together.
     pub agent_radius_on_outer_edge: bool,
     /// A set of obstacle entities that should be filter when building the [`NavMesh`].
+    ///
+    /// Entities with a [`NavMeshArea`] or an [`OffMeshLink`] are filtered the same way, to choose the [`NavMesh`]es
+    /// they are added to.
     pub filter_obstacles: EntityHashSet,
     /// The mode which filter obstacle entities that should be filter when building the [`NavMesh`].
     pub filter_obstacles_mode: FilterObstaclesMode,
+    /// Settings of the path cache of the [`NavMesh`], disabled if `None`. The default value is `None`.
+    ///
+    /// The cache is emptied each time the [`NavMesh`] is rebuilt.
+    pub path_cache: Option<PathCacheSettings>,
+    /// If path queries prefer cheaper paths using the cost of [`NavMeshArea`]s, see [`NavMesh::set_weighted_paths`].
+    /// The default value is `false`.
+    pub weighted_paths: bool,
 }
 
 impl Default for NavMeshSettings {
@@ -163,13 +187,21 @@ impl Default for NavMeshSettings {
             stitches: vec![],
             scale: Vec2::ONE,
             agent_radius: 0.0,
+            additional_agent_radii: vec![],
             agent_radius_on_outer_edge: false,
             filter_obstacles: EntityHashSet::default(),
             filter_obstacles_mode: FilterObstaclesMode::default(),
+            path_cache: None,
+            weighted_paths: false,
         }
     }
 }
 
+/// A cache of the last build from obstacles marked as [`CachableObstacle`] for each of the
+/// [`NavMeshSettings::additional_agent_radii`], on the same entity as the settings.
+#[derive(Component, Debug, Clone, Default)]
+pub(crate) struct CachedVariants(Vec<Triangulation>);
+
 /// Status of the navmesh generation
 #[derive(Component, Debug, Copy, Clone, PartialEq, Eq, Default)]
 pub enum NavMeshStatus {
@@ -214,22 +246,31 @@ impl Default for NavMeshUpdateMode {
 #[derive(Component, Debug, Copy, Clone)]
 pub struct NavMeshUpdateModeBlocking;
 
+/// A layer built for one agent radius.
+struct BuiltLayer {
+    layer: Layer,
+    areas: Vec<NavMeshArea>,
+    to_cache: Option<Triangulation>,
+}
+
+/// Builds a layer for [`NavMeshSettings::agent_radius`], then for each of
+/// [`NavMeshSettings::additional_agent_radii`] if the [`NavMesh`] has a single layer.
+///
+/// Obstacles are only converted to polygons once, and the outer edge with the cachable obstacles only
+/// simplified once, then used for all radii.
 #[cfg_attr(feature = "tracing", instrument(skip_all))]
 fn build_navmesh<T: ObstacleSource>(
     obstacles: Vec<(GlobalTransform, T)>,
     cached_obstacles: Vec<(GlobalTransform, T)>,
+    areas: Vec<(GlobalTransform, T, NavMeshArea)>,
     settings: NavMeshSettings,
+    cached_variants: Vec<Triangulation>,
     mesh_transform: Transform,
-) -> (Option<Triangulation>, Layer) {
+) -> Vec<BuiltLayer> {
     let up = (mesh_transform.forward(), settings.upward_shift);
     let scale = settings.scale;
-    let base = if settings.cached.is_none() {
-        let mut base = settings.fixed;
-        base.set_agent_radius(settings.agent_radius);
-        base.set_agent_radius_simplification(settings.simplify);
-        base.agent_radius_on_outer_edge(settings.agent_radius_on_outer_edge);
-
-        let obstacle_polys: Vec<Vec<Vec2>> = cached_obstacles
+    let to_polygons = |obstacles: &[(GlobalTransform, T)]| -> Vec<Vec<Vec2>> {
+        obstacles
             .par_iter()
             .flat_map(|(transform, obstacle)| {
                 obstacle
@@ -239,53 +280,108 @@ fn build_navmesh<T: ObstacleSource>(
             .filter_map(|p| {
                 (!p.is_empty()).then(|| p.into_par_iter().map(|v| v / scale).collect::<Vec<_>>())
             })
-            .collect();
-        
-        base.add_obstacles(obstacle_polys);
-        if settings.simplify != 0.0 {
-            base.simplify(settings.simplify);
-        }
-        base.prebuild();
-        base
-    } else {
-        settings.cached.unwrap()
+            .collect()
     };
-    let mut triangulation = base.clone();
-
-    let obstacle_polys: Vec<Vec<Vec2>> = obstacles
+    let cached_polys = to_polygons(&cached_obstacles);
+    let obstacle_polys = to_polygons(&obstacles);
+    let area_polys: Vec<(Vec<Vec2>, NavMeshArea)> = areas
         .par_iter()
-        .flat_map(|(transform, obstacle)| {
-            obstacle
+        .flat_map(|(transform, source, area)| {
+            source
                 .get_polygons(transform, &mesh_transform, up)
                 .into_par_iter()
+                .map(|p| (p, *area))
         })
-        .filter_map(|p| {
-            (!p.is_empty()).then(|| p.into_par_iter().map(|v| v / scale).collect::<Vec<_>>())
+        .filter_map(|(p, area)| {
+            (p.len() >= 3).then(|| (p.into_par_iter().map(|v| v / scale).collect(), area))
         })
         .collect();
-    triangulation.add_obstacles(obstacle_polys);
+
+    let variants = if settings.layer.is_none() {
+        settings.additional_agent_radii.len()
+    } else {
+        0
+    };
+    // Only built if a radius is not cached
+    let contours = OnceLock::new();
+    (0..=variants)
+        .into_par_iter()
+        .map(|class| {
+            let (radius, cached) = if class == 0 {
+                (settings.agent_radius, settings.cached.clone())
+            } else {
+                (
+                    settings.additional_agent_radii[class - 1],
+                    cached_variants.get(class - 1).cloned(),
+                )
+            };
+            let base = cached.unwrap_or_else(|| {
+                let mut base = contours
+                    .get_or_init(|| build_contours(&cached_polys, &settings))
+                    .clone();
+                base.set_agent_radius(radius);
+                base.prebuild();
+                base
+            });
+            build_layer(
+                base,
+                &obstacle_polys,
+                &area_polys,
+                &settings,
+                !cached_obstacles.is_empty(),
+            )
+        })
+        .collect()
+}
+
+/// The outer edge with the cachable obstacles, simplified but not yet inflated by an agent radius.
+fn build_contours(cached_polys: &[Vec<Vec2>], settings: &NavMeshSettings) -> Triangulation {
+    let mut contours = settings.fixed.clone();
+    contours.set_agent_radius_simplification(settings.simplify);
+    contours.agent_radius_on_outer_edge(settings.agent_radius_on_outer_edge);
+
+    contours.add_obstacles(cached_polys.iter().cloned());
+    if settings.simplify != 0.0 {
+        contours.simplify(settings.simplify);
+    }
+    contours
+}
+
+fn build_layer(
+    base: Triangulation,
+    obstacle_polys: &[Vec<Vec2>],
+    area_polys: &[(Vec<Vec2>, NavMeshArea)],
+    settings: &NavMeshSettings,
+    cachable: bool,
+) -> BuiltLayer {
+    let mut triangulation = base.clone();
+    triangulation.add_obstacles(obstacle_polys.iter().cloned());
 
     if settings.simplify != 0.0 {
         triangulation.simplify(settings.simplify);
     }
     let mut layer = triangulation.as_layer();
 
-    for _ in 0..settings.merge_steps {
-        layer.merge_polygons();
-    }
+    let polygon_areas = if area_polys.is_empty() {
+        for _ in 0..settings.merge_steps {
+            layer.merge_polygons();
+        }
+        vec![]
+    } else {
+        let (cut, polygon_areas) = areas::cut_areas(layer, area_polys, settings.merge_steps);
+        layer = cut;
+        polygon_areas
+    };
     #[cfg(feature = "detailed-layers")]
     {
-        layer.scale = scale;
+        layer.scale = settings.scale;
     }
     layer.remove_useless_vertices();
-    (
-        if cached_obstacles.is_empty() {
-            None
-        } else {
-            Some(base)
-        },
+    BuiltLayer {
         layer,
-    )
+        areas: polygon_areas,
+        to_cache: cachable.then_some(base),
+    }
 }
 
 fn drop_dead_tasks(
@@ -324,9 +420,10 @@ fn drop_dead_tasks(
 pub struct NavmeshUpdateTask(Arc<RwLock<Option<TaskResult>>>);
 
 struct TaskResult {
-    layer: Layer,
+    /// The layer for each radius class.
+    built: Vec<BuiltLayer>,
+    links: Vec<NavMeshLink>,
     duration: Duration,
-    to_cache: Option<Triangulation>,
 }
 
 type NavMeshToUpdateQuery<'world, 'state, 'a, 'b, 'c, 'd, 'e, 'f> = Query<
@@ -348,7 +445,11 @@ type ObstacleQueries<'world, 'state, 'a, 'b, 'c, Obstacle, Marker> = (
         'world,
         'state,
         (Entity, Ref<'a, GlobalTransform>, Ref<'b, Obstacle>),
-        (With<Marker>, Without<CachableObstacle>),
+        (
+            With<Marker>,
+            Without<CachableObstacle>,
+            Without<NavMeshArea>,
+        ),
     >,
     Query<
         'world,
@@ -359,16 +460,32 @@ type ObstacleQueries<'world, 'state, 'a, 'b, 'c, Obstacle, Marker> = (
             &'b Obstacle,
             Ref<'c, CachableObstacle>,
         ),
+        (With<Marker>, Without<NavMeshArea>),
+    >,
+    Query<
+        'world,
+        'state,
+        (
+            Entity,
+            Ref<'a, GlobalTransform>,
+            Ref<'b, Obstacle>,
+            Ref<'c, NavMeshArea>,
+        ),
         With<Marker>,
     >,
+    Query<'world, 'state, (Entity, Ref<'a, GlobalTransform>, Ref<'b, OffMeshLink>)>,
 );
 
 fn trigger_navmesh_build<Marker: Component, Obstacle: ObstacleSource>(
     mut commands: Commands,
-    (dynamic_obstacles, cachable_obstacles): ObstacleQueries<Obstacle, Marker>,
-    removed_obstacles: RemovedComponents<Marker>,
+    (dynamic_obstacles, cachable_obstacles, areas, links): ObstacleQueries<Obstacle, Marker>,
+    (removed_obstacles, removed_areas, removed_links): (
+        RemovedComponents<Marker>,
+        RemovedComponents<NavMeshArea>,
+        RemovedComponents<OffMeshLink>,
+    ),
     removed_cachable_obstacles: RemovedComponents<CachableObstacle>,
-    mut navmeshes: NavMeshToUpdateQuery,
+    (mut navmeshes, mut cached_variants): (NavMeshToUpdateQuery, Query<&mut CachedVariants>),
     time: Res<Time>,
     mut ready_to_update: Local<EntityHashMap<(f32, bool)>>,
 ) {
@@ -390,18 +507,33 @@ fn trigger_navmesh_build<Marker: Component, Obstacle: ObstacleSource>(
     if cachable_obstacles_changed {
         for (_, mut settings, ..) in &mut navmeshes {
             debug!("cache cleared due to cachable obstacle change");
-            settings.bypass_change_detection().cached = None;
+            let settings = settings.bypass_change_detection();
+            settings.cached = None;
+        }
+        for mut cached in &mut cached_variants {
+            cached.0.clear();
         }
     }
 
-    for (_, mut settings, ..) in &mut navmeshes {
+    for (entity, mut settings, ..) in &mut navmeshes {
         if settings.is_changed() {
+            if settings.layer.is_some() && !settings.additional_agent_radii.is_empty() {
+                warn!(
+                    "NavMesh {:?} updates a single layer, its additional agent radii are ignored",
+                    entity
+                );
+            }
             debug!("cache cleared due to settings change");
-            settings.bypass_change_detection().cached = None;
+            let settings = settings.bypass_change_detection();
+            settings.cached = None;
+            if let Ok(mut cached) = cached_variants.get_mut(entity) {
+                cached.0.clear();
+            }
         }
     }
 
-    let has_removed_obstacles = !removed_obstacles.is_empty();
+    let has_removed_obstacles =
+        !removed_obstacles.is_empty() || !removed_areas.is_empty() || !removed_links.is_empty();
 
     let mut to_check = navmeshes
         .iter_mut()
@@ -413,6 +545,12 @@ fn trigger_navmesh_build<Marker: Component, Obstacle: ObstacleSource>(
                 || dynamic_obstacles
                     .iter()
                     .any(|(_, t, o)| t.is_changed() || o.is_changed())
+                || areas
+                    .iter()
+                    .any(|(_, t, o, a)| t.is_changed() || o.is_changed() || a.is_changed())
+                || links
+                    .iter()
+                    .any(|(_, t, l)| t.is_changed() || l.is_changed())
             {
                 Some(entity)
             } else {
@@ -498,39 +636,81 @@ fn trigger_navmesh_build<Marker: Component, Obstacle: ObstacleSource>(
                     .collect::<Vec<_>>(),
             };
 
+            // Areas and links are filtered like obstacles
+            let kept = |e: &Entity| match settings.filter_obstacles_mode {
+                FilterObstaclesMode::All => true,
+                FilterObstaclesMode::Allow => settings.filter_obstacles.contains(e),
+                FilterObstaclesMode::Ignore => !settings.filter_obstacles.contains(e),
+            };
+            let areas_local = areas
+                .iter()
+                .filter(|(e, ..)| kept(e))
+                .map(|(_, t, o, a)| (*t, o.clone(), *a))
+                .collect::<Vec<_>>();
+
             let settings_local = settings.clone();
+            let cached_variants_local = cached_variants
+                .get(entity)
+                .map(|cached| cached.0.clone())
+                .unwrap_or_default();
             let transform_local = global_transform.compute_transform();
 
+            let world_to_mesh = world_to_mesh(&transform_local);
+            let to_navmesh = |t: &GlobalTransform, v: Vec3| {
+                world_to_mesh.transform_point3(t.transform_point(v)).xy() / settings.scale
+            };
+            // Layers are placed in the mesh at their offset, links added by a layer can reach other layers
+            let offset = if settings.layer.is_some() {
+                transform_local.translation.xz()
+            } else {
+                Vec2::ZERO
+            };
+            let links_local = links
+                .iter()
+                .filter(|(e, ..)| kept(e))
+                .map(|(_, t, l)| NavMeshLink {
+                    start: to_navmesh(&t, l.start) + offset,
+                    end: to_navmesh(&t, l.end) + offset,
+                    cost: l.cost,
+                    bidirectional: l.bidirectional,
+                    flags: l.flags,
+                })
+                .collect::<Vec<_>>();
+
             *status = NavMeshStatus::Building;
             let updating = NavmeshUpdateTask(Arc::new(RwLock::new(None)));
             let writer = updating.0.clone();
             if is_blocking.is_some() {
                 let start = Instant::now();
-                let (to_cache, layer) = build_navmesh(
+                let built = build_navmesh(
                     obstacles_local,
                     cached_obstacles,
+                    areas_local,
                     settings_local,
+                    cached_variants_local,
                     transform_local,
                 );
                 *writer.write().unwrap() = Some(TaskResult {
-                    layer,
+                    built,
+                    links: links_local,
                     duration: start.elapsed(),
-                    to_cache,
                 });
             } else {
                 AsyncComputeTaskPool::get()
                     .spawn(async move {
                         let start = Instant::now();
-                        let (to_cache, layer) = build_navmesh(
+                        let built = build_navmesh(
                             obstacles_local,
                             cached_obstacles,
+                            areas_local,
                             settings_local,
+                            cached_variants_local,
                             transform_local,
                         );
                         *writer.write().unwrap() = Some(TaskResult {
-                            layer,
+                            built,
+                            links: links_local,
                             duration: start.elapsed(),
-                            to_cache,
                         });
                     })
                     .detach();
@@ -562,17 +742,34 @@ fn update_navmesh_asset(
     for (entity, handle, task, global_transform, mut status, mut settings) in &mut live_navmeshes {
         let mut task = task.0.write().unwrap();
         if let Some(TaskResult {
-            layer,
+            built,
+            links,
             duration,
-            to_cache,
         }) = task.take()
         {
             let mut failed_stitches = vec![];
             commands.entity(entity).remove::<NavmeshUpdateTask>();
+            let mut built = built.into_iter();
+            let Some(BuiltLayer {
+                layer,
+                areas: polygon_areas,
+                to_cache,
+            }) = built.next()
+            else {
+                continue;
+            };
+            let mut variants = built.collect::<Vec<_>>();
             if to_cache.is_some() {
                 debug!("cache updated");
                 // This is internal and shouldn't trigger change detection
-                settings.bypass_change_detection().cached = to_cache;
+                let settings = settings.bypass_change_detection();
+                settings.cached = to_cache;
+                commands.entity(entity).insert(CachedVariants(
+                    variants
+                        .iter_mut()
+                        .filter_map(|variant| variant.to_cache.take())
+                        .collect(),
+                ));
             }
             debug!(
                 "navmesh {:?} ({:?}) built{}",
@@ -584,28 +781,47 @@ fn update_navmesh_asset(
                     "".to_string()
                 }
             );
-            let (previous_navmesh_transform, mut mesh, mut previously_failed) =
-                if let Some(navmesh) = navmeshes.get(&handle.0) {
-                    if let Some(mesh) = navmesh.building.as_ref() {
-                        (
-                            navmesh.transform(),
-                            mesh.mesh.clone(),
-                            mesh.failed_stitches.clone(),
-                        )
-                    } else {
-                        (navmesh.transform(), (*navmesh.get()).clone(), vec![])
-                    }
+            // Gates are toggled on the navmesh and kept through rebuilds
+            let closed_gates = navmeshes
+                .get(&handle.0)
+                .map_or(0, |navmesh| navmesh.closed_gates);
+            let (
+                previous_navmesh_transform,
+                mut mesh,
+                mut previously_failed,
+                mut layer_areas,
+                mut layer_links,
+            ) = if let Some(navmesh) = navmeshes.get(&handle.0) {
+                if let Some(mesh) = navmesh.building.as_ref() {
+                    (
+                        navmesh.transform(),
+                        mesh.mesh.clone(),
+                        mesh.failed_stitches.clone(),
+                        mesh.areas.clone(),
+                        mesh.links_by_layer.clone(),
+                    )
                 } else {
                     (
-                        Transform::IDENTITY,
-                        Mesh {
-                            layers: vec![],
-                            search_delta: settings.default_search_delta,
-                            search_steps: settings.default_search_steps,
-                        },
+                        navmesh.transform(),
+                        (*navmesh.get()).clone(),
                         vec![],
+                        navmesh.areas.clone(),
+                        navmesh.links_by_layer.clone(),
                     )
-                };
+                }
+            } else {
+                (
+                    Transform::IDENTITY,
+                    Mesh {
+                        layers: vec![],
+                        search_delta: settings.default_search_delta,
+                        search_steps: settings.default_search_steps,
+                    },
+                    vec![],
+                    vec![],
+                    vec![],
+                )
+            };
 
             if let Some(layer_id) = &settings.layer {
                 *status = NavMeshStatus::Built;
@@ -618,6 +834,14 @@ fn update_navmesh_asset(
                 }
                 mesh.remove_stitches_to_layer(*layer_id);
                 mesh.layers[*layer_id as usize] = layer;
+                if layer_areas.len() <= *layer_id as usize {
+                    layer_areas.resize(*layer_id as usize + 1, vec![]);
+                }
+                layer_areas[*layer_id as usize] = polygon_areas;
+                if layer_links.len() <= *layer_id as usize {
+                    layer_links.resize(*layer_id as usize + 1, vec![]);
+                }
+                layer_links[*layer_id as usize] = links;
                 // TODO: rotate this to get the value in the correct space
                 mesh.layers[*layer_id as usize].offset = global_transform.translation().xz();
 
@@ -696,6 +920,11 @@ fn update_navmesh_asset(
 
                 if *status == NavMeshStatus::Built && previously_failed.is_empty() {
                     let mut navmesh = NavMesh::from_polyanya_mesh(mesh);
+                    navmesh.set_areas(layer_areas);
+                    navmesh.set_links_by_layer(layer_links);
+                    navmesh.closed_gates = closed_gates;
+                    navmesh.set_path_cache(settings.path_cache);
+                    navmesh.set_weighted_paths(settings.weighted_paths);
                     if *layer_id == 0 {
                         navmesh.set_transform(global_transform.compute_transform());
                     } else {
@@ -709,15 +938,43 @@ fn update_navmesh_asset(
                     navmesh.building = Some(crate::BuildingMesh {
                         mesh,
                         failed_stitches,
+                        areas: layer_areas,
+                        links_by_layer: layer_links,
                     });
                 } else {
-                    let navmesh = NavMesh::from_polyanya_mesh(mesh);
+                    let mut navmesh = NavMesh::from_polyanya_mesh(mesh);
+                    navmesh.set_areas(layer_areas);
+                    navmesh.set_links_by_layer(layer_links);
+                    navmesh.closed_gates = closed_gates;
                     navmeshes.insert(&handle.0, navmesh);
                     *status = NavMeshStatus::Invalid;
                 }
             } else {
+                let variants = variants
+                    .into_iter()
+                    .map(|variant| {
+                        let mut navmesh = NavMesh::from_polyanya_mesh(Mesh {
+                            layers: vec![variant.layer],
+                            search_delta: mesh.search_delta,
+                            search_steps: mesh.search_steps,
+                        });
+                        navmesh.set_areas(vec![variant.areas]);
+                        navmesh.set_links_by_layer(vec![links.clone()]);
+                        navmesh.closed_gates = closed_gates;
+                        navmesh.set_path_cache(settings.path_cache);
+                        navmesh.set_weighted_paths(settings.weighted_paths);
+                        navmesh.set_transform(global_transform.compute_transform());
+                        navmesh
+                    })
+                    .collect();
                 mesh.layers = vec![layer];
                 let mut navmesh = NavMesh::from_polyanya_mesh(mesh);
+                navmesh.set_areas(vec![polygon_areas]);
+                navmesh.set_links_by_layer(vec![links]);
+                navmesh.closed_gates = closed_gates;
+                navmesh.variants = variants;
+                navmesh.set_path_cache(settings.path_cache);
+                navmesh.set_weighted_paths(settings.weighted_paths);
                 navmesh.set_transform(global_transform.compute_transform());
                 navmeshes.insert(&handle.0, navmesh);
                 *status = NavMeshStatus::Built;
@@ -787,3 +1044,257 @@ impl<Obstacle: ObstacleSource, Marker: Component> Plugin
         .register_diagnostic(Diagnostic::new(NAVMESH_BUILD_DURATION));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::primitives::Rectangle;
+
+    use super::*;
+    use crate::prelude::PrimitiveObstacle;
+
+    #[test]
+    fn build_for_each_radius() {
+        let settings = NavMeshSettings {
+            fixed: Triangulation::from_outer_edges(&[
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(10.0, 10.0),
+                vec2(0.0, 10.0),
+            ]),
+            agent_radius: 0.5,
+            additional_agent_radii: vec![1.0, 2.0],
+            ..Default::default()
+        };
+        let obstacle = (
+            GlobalTransform::from_xyz(5.0, 5.0, 0.0),
+            PrimitiveObstacle::Rectangle(Rectangle::new(2.0, 2.0)),
+        );
+        let built = build_navmesh(
+            vec![obstacle],
+            vec![],
+            vec![],
+            settings.clone(),
+            vec![],
+            Transform::IDENTITY,
+        );
+        assert_eq!(built.len(), 3);
+        let areas = built
+            .iter()
+            .map(|built| {
+                let mesh = Mesh {
+                    layers: vec![built.layer.clone()],
+                    ..Default::default()
+                };
+                crate::topology::polygons(&mesh)
+                    .map(|polygon| {
+                        crate::topology::area(&crate::topology::polygon_coords(&mesh, polygon))
+                    })
+                    .sum::<f32>()
+            })
+            .collect::<Vec<_>>();
+        assert!(areas[0] > areas[1] && areas[1] > areas[2]);
+
+        // Cachable obstacles are shared by all radii, the cached bases build the same layers
+        let built = build_navmesh::<PrimitiveObstacle>(
+            vec![],
+            vec![obstacle],
+            vec![],
+            settings.clone(),
+            vec![],
+            Transform::IDENTITY,
+        );
+        let mut cached = built.iter().map(|built| built.to_cache.clone().unwrap());
+        let rebuilt = build_navmesh::<PrimitiveObstacle>(
+            vec![],
+            vec![],
+            vec![],
+            NavMeshSettings {
+                cached: cached.next(),
+                ..settings.clone()
+            },
+            cached.collect(),
+            Transform::IDENTITY,
+        );
+        for (built, rebuilt) in built.iter().zip(&rebuilt) {
+            assert_eq!(built.layer.polygons.len(), rebuilt.layer.polygons.len());
+            assert_eq!(built.layer.vertices.len(), rebuilt.layer.vertices.len());
+        }
+
+        // Variants are only built for navmeshes with a single layer
+        let built = build_navmesh::<PrimitiveObstacle>(
+            vec![],
+            vec![],
+            vec![],
+            NavMeshSettings {
+                layer: Some(0),
+                ..settings
+            },
+            vec![],
+            Transform::IDENTITY,
+        );
+        assert_eq!(built.len(), 1);
+    }
+
+    /// An app updating a navmesh with two layers, and a link from the first one to the second one that is
+    /// only added by the second layer. Returns the app, the link and the entity of each layer.
+    fn layers_with_link() -> (App, Entity, [Entity; 2]) {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            NavmeshUpdaterPlugin::<PrimitiveObstacle>::default(),
+        ))
+        .init_asset::<NavMesh>();
+
+        // A jump from the first layer to the second one, placed 20 units to the right
+        let link = app
+            .world_mut()
+            .spawn((
+                OffMeshLink {
+                    start: vec3(9.0, 5.0, 0.0),
+                    end: vec3(21.0, 5.0, 0.0),
+                    ..Default::default()
+                },
+                GlobalTransform::IDENTITY,
+            ))
+            .id();
+        let only_link = EntityHashSet::from_iter([link]);
+        // The link is added by the second layer, its ends are still where they are in the world
+        let layers = [(0, 0.0, EntityHashSet::default()), (1, 20.0, only_link)].map(
+            |(layer, x, filter_obstacles)| {
+                app.world_mut()
+                    .spawn((
+                        NavMeshSettings {
+                            fixed: square(),
+                            layer: Some(layer),
+                            filter_obstacles,
+                            filter_obstacles_mode: FilterObstaclesMode::Allow,
+                            ..Default::default()
+                        },
+                        ManagedNavMesh::from_id(1),
+                        NavMeshUpdateMode::Direct,
+                        NavMeshUpdateModeBlocking,
+                        GlobalTransform::from_xyz(x, 0.0, 0.0),
+                    ))
+                    .id()
+            },
+        );
+        (app, link, layers)
+    }
+
+    fn square() -> Triangulation {
+        Triangulation::from_outer_edges(&[
+            vec2(0.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(0.0, 10.0),
+        ])
+    }
+
+    #[test]
+    fn links_of_each_navmesh() {
+        let (mut app, link, _) = layers_with_link();
+        let layered = ManagedNavMesh::from_id(1);
+        let only_link = EntityHashSet::from_iter([link]);
+        // Another navmesh that ignores the link
+        let other = ManagedNavMesh::from_id(2);
+        app.world_mut().spawn((
+            NavMeshSettings {
+                fixed: square(),
+                filter_obstacles: only_link,
+                filter_obstacles_mode: FilterObstaclesMode::Ignore,
+                ..Default::default()
+            },
+            ManagedNavMesh::from_id(2),
+            NavMeshUpdateMode::Direct,
+            NavMeshUpdateModeBlocking,
+        ));
+        for _ in 0..4 {
+            app.update();
+        }
+
+        let navmeshes = app.world().resource::<Assets<NavMesh>>();
+        let navmesh = navmeshes.get(&*layered).unwrap();
+        assert_eq!(navmesh.get().layers.len(), 2);
+        assert_eq!(navmesh.links().len(), 1);
+        assert_eq!(navmesh.links()[0].start, vec2(9.0, 5.0));
+        assert_eq!(navmesh.links()[0].end, vec2(21.0, 5.0));
+        assert!(navmesh.path(vec2(1.0, 5.0), vec2(29.0, 5.0)).is_some());
+        assert!(navmeshes.get(&*other).unwrap().links().is_empty());
+    }
+    #[test]
+    fn areas_of_each_navmesh() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            NavmeshUpdaterPlugin::<PrimitiveObstacle>::default(),
+        ))
+        .init_asset::<NavMesh>();
+
+        let water = app
+            .world_mut()
+            .spawn((
+                PrimitiveObstacle::Rectangle(Rectangle::new(4.0, 4.0)),
+                NavMeshArea {
+                    flags: 2,
+                    ..Default::default()
+                },
+                GlobalTransform::from_xyz(5.0, 5.0, 0.0),
+            ))
+            .id();
+        for (id, filter_obstacles_mode) in [
+            (1, FilterObstaclesMode::All),
+            (2, FilterObstaclesMode::Ignore),
+        ] {
+            app.world_mut().spawn((
+                NavMeshSettings {
+                    fixed: square(),
+                    filter_obstacles: EntityHashSet::from_iter([water]),
+                    filter_obstacles_mode,
+                    ..Default::default()
+                },
+                ManagedNavMesh::from_id(id),
+                NavMeshUpdateMode::Direct,
+                NavMeshUpdateModeBlocking,
+            ));
+        }
+        for _ in 0..4 {
+            app.update();
+        }
+
+        let navmeshes = app.world().resource::<Assets<NavMesh>>();
+        let flags_at = |id: u128| {
+            let navmesh = navmeshes.get(&*ManagedNavMesh::from_id(id)).unwrap();
+            navmesh.polygon_flags(navmesh.locate(vec2(5.0, 5.0)).unwrap())
+        };
+        assert_eq!(flags_at(1), 2);
+        assert_eq!(flags_at(2), NavMeshArea::DEFAULT_FLAGS);
+    }
+
+    #[test]
+    fn reloaded_links_by_layer() {
+        let (mut app, _, [first_layer, _]) = layers_with_link();
+        for _ in 0..4 {
+            app.update();
+        }
+        let layered = ManagedNavMesh::from_id(1);
+        let mut navmeshes = app.world_mut().resource_mut::<Assets<NavMesh>>();
+        let reloaded =
+            NavMesh::from_binary(&navmeshes.get(&*layered).unwrap().to_binary()).unwrap();
+        navmeshes.insert(&*layered, reloaded);
+
+        // Rebuilding the first layer keeps the link added by the second one
+        app.world_mut()
+            .get_mut::<NavMeshSettings>(first_layer)
+            .unwrap()
+            .set_changed();
+        for _ in 0..4 {
+            app.update();
+        }
+        let navmeshes = app.world().resource::<Assets<NavMesh>>();
+        let navmesh = navmeshes.get(&*layered).unwrap();
+        assert_eq!(navmesh.links().len(), 1);
+        assert!(navmesh.path(vec2(1.0, 5.0), vec2(29.0, 5.0)).is_some());
+    }
+}
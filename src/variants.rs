@@ -0,0 +1,76 @@
+use crate::NavMesh;
+
+impl NavMesh {
+    /// The [`NavMesh`] built for an agent radius class.
+    ///
+    /// Class `0` is this [`NavMesh`], and the following classes are its variants for other agent radii,
+    /// as declared in [`NavMeshSettings::additional_agent_radii`](crate::prelude::NavMeshSettings::additional_agent_radii).
+    /// Returns `None` if there is no variant for this class.
+    pub fn radius_class(&self, class: usize) -> Option<&NavMesh> {
+        match class {
+            0 => Some(self),
+            class => self.variants.get(class - 1),
+        }
+    }
+
+    /// Number of agent radius classes of this [`NavMesh`], including itself.
+    pub fn radius_classes(&self) -> usize {
+        self.variants.len() + 1
+    }
+
+    /// Sets the variants of this [`NavMesh`] for other agent radii, available from class `1` with
+    /// [`NavMesh::radius_class`].
+    pub fn set_radius_variants(&mut self, variants: Vec<NavMesh>) {
+        self.variants = variants;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+    use polyanya::Triangulation;
+
+    use super::*;
+
+    fn with_radius(radius: f32) -> NavMesh {
+        // A corridor between two obstacles, 2 units wide
+        let mut triangulation = Triangulation::from_outer_edges(&[
+            vec2(0.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(0.0, 10.0),
+        ]);
+        triangulation.add_obstacles([
+            vec![
+                vec2(4.0, -1.0),
+                vec2(6.0, -1.0),
+                vec2(6.0, 4.0),
+                vec2(4.0, 4.0),
+            ],
+            vec![
+                vec2(4.0, 6.0),
+                vec2(6.0, 6.0),
+                vec2(6.0, 11.0),
+                vec2(4.0, 11.0),
+            ],
+        ]);
+        triangulation.set_agent_radius(radius);
+        NavMesh::from_polyanya_mesh(triangulation.as_navmesh())
+    }
+
+    #[test]
+    fn select_radius_class() {
+        let mut navmesh = with_radius(0.5);
+        navmesh.set_radius_variants(vec![with_radius(1.5)]);
+        assert_eq!(navmesh.radius_classes(), 2);
+        assert!(navmesh.radius_class(2).is_none());
+
+        let (from, to) = (vec2(1.0, 5.0), vec2(9.0, 5.0));
+        assert!(navmesh.radius_class(0).unwrap().path(from, to).is_some());
+        // Too large to go through the corridor
+        assert!(navmesh.radius_class(1).unwrap().path(from, to).is_none());
+
+        navmesh.close_gates(1 << 3);
+        assert_eq!(navmesh.radius_class(1).unwrap().closed_gates(), 1 << 3);
+    }
+}